@@ -0,0 +1,418 @@
+//! Event-stream arrival models.
+//!
+//! The releases of a task are described by an _arrival curve_ `η⁺(Δ)`, i.e. the largest
+//! number of jobs released within any interval of length `Δ`, or equivalently by its
+//! pseudo-inverse `δ⁻(n)`, i.e. the shortest interval between the first and the last of
+//! `n` consecutive releases, as in Richter's "Compositional scheduling analysis using
+//! standard event models" (PhD thesis, TU Braunschweig, 2005).
+//!
+//! Sporadic tasks, periodic tasks with jitter and PJD tasks (see
+//! [`Task::distance`]) have arrival curves given by their parameters, which
+//! [`Task::max_jobs`] implements directly; a [`Curve`] also describes arbitrary bursty
+//! streams, which are attached to tasks by [`BurstyTask`]. The tests in this module
+//! accept any iterator of [`Arrivals`] items, so that the two can be analyzed together.
+
+use crate::task::{Task, AsTask, Time};
+
+use dashu::rational::RBig;
+
+/// An arrival curve, given by the shortest distances between the first and the last
+/// of `n` consecutive releases, for `n` up to some bound, and by a period of releases
+/// after that.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Curve {
+    distances: Box<[Time]>,
+    period: Time
+}
+
+impl Curve {
+    /// Constructs a new `Curve` such that any `n + 2` consecutive releases span at least
+    /// `distances[n]`, and any further release follows the previous one by at least
+    /// `period`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `distances` is decreasing anywhere or if `period` is zero.
+    pub fn new(distances: impl IntoIterator<Item = Time>, period: Time) -> Self {
+        let distances = distances.into_iter().collect::<Box<_>>();
+
+        assert!(period > 0);
+        assert!(distances.windows(2).all(|w| w[0] <= w[1]));
+
+        Self { distances, period }
+    }
+
+    /// Constructs the `Curve` of sporadic releases with minimum inter-arrival time `period`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn sporadic(period: Time) -> Self {
+        Self::new([], period)
+    }
+
+    /// Constructs the `Curve` of the PJD model with period `period`, jitter `jitter` and
+    /// minimum distance `distance`, as described by [`Task::distance`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn pjd(period: Time, jitter: Time, distance: Time) -> Self {
+        if distance >= period {
+            return Self::sporadic(distance);
+        }
+
+        // past this many releases, the jitter is absorbed and releases are periodic
+        let burst = jitter.div_ceil(period - distance);
+
+        Self::new((1 ..= burst).map(|n| (n * period).saturating_sub(jitter).max(n * distance)), period)
+    }
+
+    /// Returns the shortest interval spanned by `n` consecutive releases, i.e. `δ⁻(n)`.
+    pub fn min_span(&self, n: Time) -> Time {
+        let known = self.distances.len() as Time + 1;
+
+        if n <= 1 {
+            0
+        } else if n <= known {
+            self.distances[(n - 2) as usize]
+        } else {
+            self.min_span(known) + (n - known) * self.period
+        }
+    }
+
+    /// Returns the largest number of releases within an interval of length `len`,
+    /// i.e. `η⁺(len)`.
+    pub fn max_jobs(&self, len: Time) -> Time {
+        if len == 0 {
+            return 0;
+        }
+
+        // `n` releases fit within the interval iff they span less than its length
+        let (mut lo, mut hi) = (1, self.burst() + len.div_ceil(self.period));
+
+        while lo < hi {
+            let mid = lo + (hi - lo).div_ceil(2);
+
+            if self.min_span(mid) < len {
+                lo = mid;
+            } else {
+                hi = mid - 1;
+            }
+        }
+
+        lo
+    }
+
+    /// Returns the largest number of releases in excess of the long-term rate, such
+    /// that `η⁺(len)` is at most `len / period` plus this number.
+    pub fn burst(&self) -> Time {
+        self.distances.len() as Time + 1
+    }
+
+    /// Returns the long-term period of releases.
+    pub fn period(&self) -> Time {
+        self.period
+    }
+}
+
+/// A task whose releases are described by an arbitrary [`Curve`].
+#[derive(Clone)]
+pub struct BurstyTask {
+    /// The task, whose period, jitter and minimum distance are superseded by `curve`.
+    ///
+    /// Its deadline is measured from the release of each job.
+    pub task: Task,
+    /// The arrival curve of the releases of the task.
+    pub curve: Curve
+}
+
+/// A `BurstyTask` is analyzed by tests for sporadic tasks as its task, with its long-term
+/// period as period and enough jitter to cover its bursts.
+impl AsTask for BurstyTask {
+    fn as_task(&self) -> Task {
+        let period = self.curve.period();
+
+        Task {
+            period,
+            jitter: (self.curve.burst() - 1) * period,
+            distance: 0,
+            ..self.task
+        }
+    }
+}
+
+/// Trait for objects that can be analyzed as a [`Task`] with an arrival curve.
+pub trait Arrivals {
+    /// Returns the task and the arrival curve of its releases; the deadline of the task,
+    /// shortened by its jitter, is measured from the release of each job.
+    fn arrivals(&self) -> (Task, Curve);
+}
+
+/// The curve of a `Task` is that of its PJD parameters.
+impl Arrivals for Task {
+    fn arrivals(&self) -> (Task, Curve) {
+        (*self, Curve::pjd(self.period, self.jitter, self.distance))
+    }
+}
+
+impl Arrivals for BurstyTask {
+    fn arrivals(&self) -> (Task, Curve) {
+        (self.task.with_jitter(0), self.curve.clone())
+    }
+}
+
+impl<T: Arrivals + ?Sized> Arrivals for &T {
+    fn arrivals(&self) -> (Task, Curve) {
+        (**self).arrivals()
+    }
+}
+
+/// Collects the tasks and curves that the items of `ts` are analyzed as.
+fn collect(ts: impl IntoIterator<Item: Arrivals>) -> Box<[(Task, Curve)]> {
+    ts.into_iter()
+      .map(|t| t.arrivals())
+      .collect()
+}
+
+/// Response-time analysis for uniprocessor fixed-priority scheduling of tasks with
+/// arbitrary arrival curves, after Richter's busy-window analysis in "Compositional
+/// scheduling analysis using standard event models" (PhD thesis, TU Braunschweig, 2005):
+/// the `q`-th job of a task in a busy window is released no earlier than `δ⁻(q)`,
+/// and higher-priority tasks release at most `η⁺` jobs within it.
+///
+/// Priorities and `blocking` are as for [`uni::fp`](`crate::bound::uni::fp`); the
+/// response time of a task with release jitter, e.g. a PJD [`Task`], is measured from the
+/// arrival of each job, i.e. its jitter is added to the response time from its release.
+/// For periodic tasks with jitter and without minimum distance, [`uni::fp`](`crate::bound::uni::fp`)
+/// is less pessimistic, as it also accounts for the periodic arrivals of later jobs in the
+/// busy window.
+///
+/// Returns a bound on the response time of each task, or `None` for tasks that can miss
+/// their deadline or whose long-term utilization, together with that of higher-priority
+/// tasks, is not below `1`.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn fp(ts: impl IntoIterator<Item: Arrivals>, blocking: Option<&[Time]>) -> Box<[Option<Time>]> {
+    let ts = collect(ts);
+
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), ts.len());
+    }
+
+    (0 .. ts.len()).map(|k| {
+        let (tk, ck) = &ts[k];
+        let hp = (0 .. ts.len()).filter(|i| *i != k && ts[*i].0.priority <= tk.priority)
+                                .collect::<Box<_>>();
+
+        // busy windows are only bounded if their long-term utilization is below 1
+        let util = hp.iter().chain([&k]).fold(RBig::ZERO, |acc, i| {
+            acc + RBig::from(ts[*i].0.cost) / RBig::from(ts[*i].1.period())
+        });
+
+        if util >= RBig::ONE {
+            return None;
+        }
+
+        let base = blocking.map_or(0, |b| b[k]);
+        let deadline = tk.deadline.checked_sub(tk.jitter)?;
+        let mut resp = 0;
+
+        for q in 1 .. {
+            // completion of the q-th job in the busy window
+            let mut w = base + q * tk.cost;
+
+            loop {
+                let next = base + q * tk.cost + hp.iter().map(|i| {
+                    let (ti, ci) = &ts[*i];
+                    ci.max_jobs(w) * ti.cost
+                }).sum::<Time>();
+
+                if next.saturating_sub(ck.min_span(q)) > deadline {
+                    return None;
+                } else if next == w {
+                    break;
+                }
+
+                w = next;
+            }
+
+            resp = resp.max(w - ck.min_span(q).min(w));
+
+            // the busy window ends before the next release
+            if w <= ck.min_span(q + 1) {
+                break;
+            }
+        }
+
+        Some(resp + tk.jitter)
+    }).collect()
+}
+
+/// Processor-demand test for uniprocessor EDF of tasks with arbitrary arrival curves:
+/// the jobs of each task with both release and deadline within an interval of length
+/// `t` are at most `η⁺(t - D + 1)`, where `D` is its deadline measured from each release.
+///
+/// `blocking` is as for [`uni::edf`](`crate::bound::uni::edf`). For [`Task`]s without
+/// minimum distance, [`uni::edf`](`crate::bound::uni::edf`) is exact and less pessimistic
+/// with release jitter, as it measures deadlines from arrivals. The test requires a
+/// long-term utilization below `1`, and otherwise fails.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn edf(ts: impl IntoIterator<Item: Arrivals>, blocking: Option<&[Time]>) -> bool {
+    let ts = collect(ts);
+
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), ts.len());
+    }
+
+    let Some(windows) = ts.iter()
+                          .map(|(t, _)| t.deadline.checked_sub(t.jitter))
+                          .collect::<Option<Box<_>>>() else {
+        return false;
+    };
+
+    let util = ts.iter().fold(RBig::ZERO, |acc, (t, c)| {
+        acc + RBig::from(t.cost) / RBig::from(c.period())
+    });
+
+    if util >= RBig::ONE {
+        return false;
+    }
+
+    // each demand is at most its long-term utilization times the interval, plus its bursts
+    let burst = ts.iter().fold(RBig::ZERO, |acc, (t, c)| {
+        acc + RBig::from(t.cost * (c.burst() + 1))
+    });
+
+    let max_dl = ts.iter().map(|(t, _)| t.deadline).max().unwrap_or_default();
+    let limit = Time::try_from((burst / (RBig::ONE - util)).ceil()).unwrap_or(Time::MAX)
+                                                                   .max(max_dl);
+
+    let dbf = |t: Time| ts.iter().zip(&windows).filter(|(_, d)| t >= **d).map(|((task, c), d)| {
+        c.max_jobs(t - d + 1) * task.cost
+    }).sum::<Time>();
+
+    // demand increases when a further release fits before the deadline, so the largest
+    // such point before `t` follows from the number of releases that fit before it
+    let prev = |t: Time| ts.iter().zip(&windows).filter(|(_, d)| t > **d).map(|((_, c), d)| {
+        d + c.min_span(c.max_jobs(t - d))
+    }).max();
+
+    // the remaining points are checked as in `uni::edf`, by Zhang and Burns' QPA
+    let low = match blocking {
+        Some(_) => max_dl,
+        None => windows.iter().copied().min().unwrap_or_default()
+    };
+
+    if let Some(mut t) = prev(limit + 1) {
+        loop {
+            let demand = dbf(t);
+
+            if demand > t {
+                return false;
+            } else if demand <= low {
+                break;
+            }
+
+            t = if demand < t {
+                demand
+            } else if let Some(p) = prev(t) {
+                p
+            } else {
+                break;
+            };
+        }
+    }
+
+    let Some(blocking) = blocking else {
+        return true;
+    };
+
+    let mut next = prev(max_dl + 1);
+
+    while let Some(t) = next {
+        let block = ts.iter()
+                      .zip(blocking)
+                      .filter(|((task, _), _)| task.deadline > t)
+                      .map(|(_, b)| *b)
+                      .max()
+                      .unwrap_or_default();
+
+        if dbf(t) + block > t {
+            return false;
+        }
+
+        next = prev(t);
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bound::uni;
+
+    /// A task releasing three jobs at once, every `10` time units.
+    fn bursty() -> BurstyTask {
+        BurstyTask { task: Task::new(1, 10), curve: Curve::new([0, 0], 10) }
+    }
+
+    #[test]
+    fn pjd() {
+        // with period 10, jitter 25 and minimum distance 2, the first releases are
+        // limited by the distance and later ones by the period
+        let c = Curve::pjd(10, 25, 2);
+
+        assert_eq!((1 ..= 7).map(|n| c.min_span(n)).collect::<Vec<_>>(), [0, 2, 4, 6, 15, 25, 35]);
+        assert_eq!((c.max_jobs(0), c.max_jobs(3), c.max_jobs(7), c.max_jobs(16)), (0, 2, 4, 5));
+        assert_eq!((c.burst(), c.period()), (5, 10));
+
+        let t = Task::new(1, 10).with_jitter(25).with_distance(2);
+
+        for len in 0 .. 100 {
+            assert_eq!(c.max_jobs(len), t.max_jobs(len));
+        }
+    }
+
+    #[test]
+    fn sporadic() {
+        let c = Curve::sporadic(10);
+
+        assert_eq!(Curve::pjd(10, 0, 0), c);
+        assert_eq!(Curve::pjd(10, 5, 12), Curve::sporadic(12));
+        assert_eq!((c.min_span(3), c.max_jobs(10), c.max_jobs(11)), (20, 1, 2));
+    }
+
+    #[test]
+    fn sporadic_tasks() {
+        let ts = [Task::new(1, 4),
+                  Task { priority: 1, ..Task::new(2, 6) },
+                  Task { priority: 2, ..Task::new(3, 10) }];
+
+        assert_eq!(fp(ts, None), uni::fp(ts, None));
+        assert!(edf(ts, None) && uni::edf(ts, None));
+
+        // unlike `uni::edf`, the test fails at full utilization
+        assert!(!edf([Task::new(2, 4), Task::new(3, 6)], None));
+    }
+
+    #[test]
+    fn bursts() {
+        let low = Task { priority: 1, ..Task::new(2, 20) };
+        let ts: [&dyn Arrivals; 2] = [&bursty(), &low];
+
+        // the sporadic approximation of the bursty task has jitter beyond its deadline
+        assert_eq!(fp(ts, None).as_ref(), [Some(3), Some(5)]);
+        assert_eq!(uni::fp([bursty().as_task(), low], None).as_ref(), [None, Some(5)]);
+
+        assert!(edf(ts, None));
+        assert!(!edf(ts, Some(&[0, 8])));
+    }
+}
@@ -0,0 +1,254 @@
+//! Exact tests for global scheduling of small task-sets.
+//!
+//! The tests in this module explore every possible schedule of a task-set in discrete
+//! time, and hence serve as ground truth to validate the sufficient tests in the other
+//! modules and to measure their pessimism. Their cost grows exponentially with the
+//! size of the task-set and its parameters, so they stop and report
+//! [`Verdict::GaveUp`] after a given number of states or time steps.
+//!
+//! The scheduler is assumed to be work-conserving and deterministic, breaking ties
+//! between equal-priority jobs by the index of their task; ties broken otherwise may
+//! yield different results.
+
+use super::collect;
+
+use crate::task::{self, Task, AsTask, Time, Policy};
+
+use std::{cmp::Reverse, collections::HashSet};
+
+/// The result of an exact test.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Verdict {
+    /// No deadline can be missed.
+    Schedulable,
+    /// Some deadline can be missed.
+    Unschedulable,
+    /// The limit was reached before the test could decide.
+    GaveUp
+}
+
+/// The state of a task in a schedule: the remaining cost of its pending job, if any,
+/// and the time until its next job can arrive.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+struct State {
+    left: Time,
+    next: Time
+}
+
+/// Executes the pending jobs of `ts` with the highest priority under `policy` on
+/// `num_cpus` CPUs for one time unit, starting from `states`.
+///
+/// Returns `false` if a pending job can no longer meet its deadline.
+fn advance(ts: &[Task], states: &mut [State], policy: Policy, num_cpus: usize) -> bool {
+    // time until the deadline of the pending job of each task
+    let slack = |i: usize, s: &State| s.next.saturating_sub(ts[i].period - ts[i].deadline);
+
+    let mut pending = (0 .. ts.len()).filter(|i| states[*i].left > 0).collect::<Vec<_>>();
+
+    match policy {
+        Policy::Fixed => pending.sort_by_key(|i| (ts[*i].priority, *i)),
+        Policy::Edf   => pending.sort_by_key(|i| (slack(*i, &states[*i]), *i)),
+        // time since the arrival of the pending job, longest first
+        Policy::Fifo  => pending.sort_by_key(|i| (Reverse(ts[*i].period - states[*i].next), *i))
+    }
+
+    for i in pending.into_iter().take(num_cpus) {
+        states[i].left -= 1;
+    }
+
+    for s in states.iter_mut() {
+        s.next = s.next.saturating_sub(1);
+    }
+
+    states.iter().enumerate().all(|(i, s)| s.left <= slack(i, s))
+}
+
+/// Baker and Cirinei's exact test for global scheduling of sporadic tasks, as described
+/// in "Brute-force determination of multiprocessor schedulability for sets of sporadic
+/// hard-deadline tasks" (OPODIS, 2007), under `policy` on `num_cpus` CPUs.
+///
+/// Every reachable state of the system is explored, branching on every set of tasks
+/// that may release a job at each time instant; the test gives up after visiting
+/// `max_states` distinct states.
+///
+/// Returns the verdict if `ts` is constrained and has no release jitter, otherwise `None`.
+pub fn sporadic(
+    ts: impl IntoIterator<Item: AsTask>,
+    num_cpus: usize,
+    policy: Policy,
+    max_states: usize
+) -> Option<Verdict> {
+    let ts = collect(ts);
+
+    if ts.iter().any(|t| t.deadline > t.period || t.jitter > 0) {
+        return None;
+    }
+
+    let start = vec![State { left: 0, next: 0 }; ts.len()].into_boxed_slice();
+    let mut seen = HashSet::from([start.clone()]);
+    let mut stack = vec![start];
+
+    while let Some(states) = stack.pop() {
+        let ready = (0 .. ts.len()).filter(|i| states[*i] == State { left: 0, next: 0 })
+                                   .collect::<Box<_>>();
+
+        for mask in 0 .. 1_usize << ready.len() {
+            let mut next = states.clone();
+
+            for (b, i) in ready.iter().enumerate() {
+                if mask >> b & 1 == 1 {
+                    next[*i] = State { left: ts[*i].cost, next: ts[*i].period };
+                }
+            }
+
+            if !advance(&ts, &mut next, policy, num_cpus) {
+                return Some(Verdict::Unschedulable);
+            } else if seen.contains(&next) {
+                continue;
+            } else if seen.len() >= max_states {
+                return Some(Verdict::GaveUp);
+            }
+
+            seen.insert(next.clone());
+            stack.push(next);
+        }
+    }
+
+    Some(Verdict::Schedulable)
+}
+
+/// Exact test for global scheduling of periodic tasks released at their
+/// [`offset`](`Task::offset`) and then exactly once per period, under `policy` on
+/// `num_cpus` CPUs.
+///
+/// The schedule is simulated from time `0` until the state of the system repeats at two
+/// consecutive multiples of the hyperperiod past the largest offset, as from then on the
+/// schedule is periodic; the test gives up if this does not happen within `max_time`
+/// time units, or if the hyperperiod does not fit in a [`Time`].
+///
+/// Returns the verdict if `ts` is constrained and has no release jitter, otherwise `None`.
+pub fn periodic(
+    ts: impl IntoIterator<Item: AsTask>,
+    num_cpus: usize,
+    policy: Policy,
+    max_time: Time
+) -> Option<Verdict> {
+    let ts = collect(ts);
+
+    if ts.iter().any(|t| t.deadline > t.period || t.jitter > 0) {
+        return None;
+    }
+
+    let Some(hyper) = task::hyperperiod(ts.iter().map(|t| t.period)) else {
+        return Some(Verdict::GaveUp);
+    };
+
+    let start = ts.iter().map(|t| t.offset).max().unwrap_or_default();
+    let mut states = vec![State { left: 0, next: 0 }; ts.len()].into_boxed_slice();
+    let mut last = None;
+
+    for t in 0 ..= max_time {
+        if t >= start && (t - start) % hyper == 0 {
+            if last.as_ref() == Some(&states) {
+                return Some(Verdict::Schedulable);
+            }
+
+            last = Some(states.clone());
+        }
+
+        for (task, s) in ts.iter().zip(states.iter_mut()) {
+            if t >= task.offset && (t - task.offset) % task.period == 0 {
+                *s = State { left: task.cost, next: task.period };
+            }
+        }
+
+        if !advance(&ts, &mut states, policy, num_cpus) {
+            return Some(Verdict::Unschedulable);
+        }
+    }
+
+    Some(Verdict::GaveUp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LIMIT: usize = 1_000_000;
+
+    fn task(cost: Time, period: Time, priority: u64) -> Task {
+        Task { priority, ..Task::new(cost, period) }
+    }
+
+    #[test]
+    fn dhall_effect() {
+        // EDF gives the light tasks priority over the heavy one upon synchronous release
+        let ts = [task(2, 20, 1), task(2, 20, 1), task(20, 21, 0)];
+
+        assert_eq!(sporadic(ts, 2, Policy::Edf, LIMIT), Some(Verdict::Unschedulable));
+        assert_eq!(periodic(ts, 2, Policy::Edf, 1000), Some(Verdict::Unschedulable));
+
+        // giving the heavy task the highest priority avoids the effect
+        assert_eq!(sporadic(ts, 2, Policy::Fixed, LIMIT), Some(Verdict::Schedulable));
+        assert_eq!(periodic(ts, 2, Policy::Fixed, 1000), Some(Verdict::Schedulable));
+    }
+
+    #[test]
+    fn uniprocessor() {
+        // Buttazzo's example, whose last task completes exactly at its deadline under
+        // rate-monotonic priorities
+        let ts = [task(1, 4, 0), task(2, 6, 1), task(3, 10, 2)];
+
+        for policy in [Policy::Fixed, Policy::Edf] {
+            assert_eq!(sporadic(ts, 1, policy, LIMIT), Some(Verdict::Schedulable));
+            assert_eq!(periodic(ts, 1, policy, 1000), Some(Verdict::Schedulable));
+        }
+
+        // with one more unit of cost, only EDF can schedule it
+        let ts = [task(1, 4, 0), task(2, 6, 1), task(4, 10, 2)];
+        assert_eq!(sporadic(ts, 1, Policy::Fixed, LIMIT), Some(Verdict::Unschedulable));
+        assert_eq!(sporadic(ts, 1, Policy::Edf, LIMIT), Some(Verdict::Schedulable));
+
+        let ts = [task(1, 4, 0), task(2, 6, 1), task(5, 10, 2)];
+        assert_eq!(periodic(ts, 1, Policy::Edf, 1000), Some(Verdict::Unschedulable));
+    }
+
+    #[test]
+    fn fifo_arrivals() {
+        // the jobs of the second task always arrive while one of the first task is
+        // pending, and must not overtake it despite the shorter period of their task
+        let ts = [Task::new(3, 99).with_deadline(3), Task { priority: 1, ..Task::new(1, 3).with_offset(2) }];
+
+        assert_eq!(periodic(ts, 1, Policy::Fixed, 100_000), Some(Verdict::Schedulable));
+        assert_eq!(periodic(ts, 1, Policy::Fifo, 100_000), Some(Verdict::Schedulable));
+        assert_eq!(periodic([ts[1], ts[0]], 1, Policy::Fifo, 100_000), Some(Verdict::Schedulable));
+
+        // synchronous arrivals are served in index order
+        let ts = [Task::new(2, 4).with_deadline(3), Task::new(1, 4).with_deadline(1)];
+
+        assert_eq!(periodic(ts, 1, Policy::Fifo, 1000), Some(Verdict::Unschedulable));
+        assert_eq!(periodic([ts[1], ts[0]], 1, Policy::Fifo, 1000), Some(Verdict::Schedulable));
+    }
+
+    #[test]
+    fn offsets() {
+        let ts = [Task::new(2, 4).with_deadline(2), Task::new(2, 4).with_deadline(2)];
+        let shifted = [ts[0], ts[1].with_offset(2)];
+
+        assert_eq!(periodic(ts, 1, Policy::Edf, 1000), Some(Verdict::Unschedulable));
+        assert_eq!(periodic(shifted, 1, Policy::Edf, 1000), Some(Verdict::Schedulable));
+
+        // sporadic releases may still be synchronous
+        assert_eq!(sporadic(shifted, 1, Policy::Edf, LIMIT), Some(Verdict::Unschedulable));
+    }
+
+    #[test]
+    fn limits() {
+        let ts = [task(1, 4, 0), task(2, 6, 1), task(3, 10, 2)];
+
+        assert_eq!(sporadic(ts, 1, Policy::Fixed, 2), Some(Verdict::GaveUp));
+        assert_eq!(periodic(ts, 1, Policy::Fixed, 12), Some(Verdict::GaveUp));
+        assert_eq!(sporadic([Task::new(1, 4).with_jitter(1)], 1, Policy::Fixed, LIMIT), None);
+        assert_eq!(periodic([Task::new(1, 4).with_deadline(5)], 1, Policy::Fixed, 1000), None);
+    }
+}
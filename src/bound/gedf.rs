@@ -0,0 +1,292 @@
+//! Hard real-time tests for global EDF.
+//!
+//! All tests in this module are sufficient tests for sporadic tasks scheduled by
+//! global EDF on `num_cpus` identical CPUs; except for [`gfb`], they require
+//! constrained deadlines and return `None` otherwise. Deadlines and response times
+//! are measured from the arrival of each job, i.e. they include its release jitter.
+
+use super::collect;
+
+use crate::task::{Task, AsTask, Set, Time};
+
+use dashu::rational::RBig;
+
+/// Returns the density of `task`, using the minimum of its deadline and period.
+fn density(task: &Task) -> RBig {
+    task.density().canonicalize()
+}
+
+/// Tests task-set `ts` using Goossens, Funk and Baruah's density bound (GFB).
+///
+/// The task-set is deemed schedulable if its total density does not exceed
+/// `num_cpus - (num_cpus - 1) * d`, where `d` is the largest density of a single task.
+/// Densities are computed using the minimum of deadline and period, so the test is
+/// valid for arbitrary deadlines as well. Task-sets with release jitter are rejected.
+pub fn gfb(ts: impl IntoIterator<Item: AsTask>, num_cpus: usize) -> bool {
+    let ts = collect(ts);
+
+    if ts.iter().any(|t| t.jitter > 0) {
+        return false;
+    }
+
+    let dens = ts.iter().map(density).collect::<Box<_>>();
+    let max = dens.iter().max().cloned().unwrap_or_default();
+    let sum = dens.iter().fold(RBig::ZERO, |acc, d| acc + d);
+
+    sum <= RBig::from(num_cpus) - RBig::from(num_cpus.saturating_sub(1)) * max
+}
+
+/// Tests task-set `ts` using Baker's test (BAK), in the form given in
+/// "An analysis of EDF schedulability on a multiprocessor" (IEEE TPDS, 2005).
+///
+/// Returns the result of the test if `ts` is constrained and has no release jitter,
+/// otherwise `None`.
+pub fn bak(ts: impl IntoIterator<Item: AsTask>, num_cpus: usize) -> Option<bool> {
+    let ts = collect(ts);
+
+    if !ts.iter().constrained() || ts.iter().any(|t| t.jitter > 0) {
+        return None;
+    }
+
+    let util = |t: &Task| RBig::from(t.cost) / RBig::from(t.period);
+    let m = RBig::from(num_cpus);
+
+    Some(ts.iter().all(|k| {
+        let dk = RBig::from(k.deadline);
+        let lambda_k = RBig::from(k.cost) / &dk;
+
+        let mut lambdas = ts.iter()
+                            .map(util)
+                            .filter(|u| *u >= lambda_k)
+                            .collect::<Vec<_>>();
+        lambdas.push(lambda_k);
+
+        lambdas.into_iter().any(|lambda| {
+            let sum = ts.iter().fold(RBig::ZERO, |acc, i| {
+                let ui = util(i);
+                let mut beta = &ui * (RBig::ONE + RBig::from(i.period - i.deadline) / &dk);
+
+                if ui > lambda {
+                    beta += (RBig::from(i.cost) - &lambda * RBig::from(i.period)) / &dk;
+                }
+
+                acc + beta.min(RBig::ONE)
+            });
+
+            sum <= &m * (RBig::ONE - &lambda) + &lambda
+        })
+    }))
+}
+
+/// Returns an upper bound on the workload of `task` within a window of length
+/// `len` ending at the deadline of a job of another task, assuming that every job
+/// of `task` completes at least `slack` time units before its deadline.
+///
+/// Since only jobs with an earlier deadline are counted, and deadlines are measured
+/// from arrivals, the bound does not depend on the release jitter of `task`.
+pub(crate) fn edf_workload(task: &Task, len: Time, slack: Time) -> Time {
+    let num = (len + task.period)
+              .checked_sub(task.deadline)
+              .map_or(0, |x| x / task.period);
+
+    num * task.cost + task.cost.min(
+        len.saturating_sub(num * task.period + slack)
+    )
+}
+
+/// Returns the length of the window between the release of a job of `task` and its
+/// deadline, in the worst case for release jitter.
+fn window(task: &Task) -> Option<Time> {
+    task.deadline.checked_sub(task.jitter)
+}
+
+/// Tests task-set `ts` using Bertogna, Cirinei and Lipari's test (BCL), in the
+/// form given in "Schedulability analysis of global scheduling algorithms on
+/// multiprocessor platforms" (IEEE TPDS, 2009).
+///
+/// Returns the result of the test if `ts` is constrained, otherwise `None`.
+pub fn bcl(ts: impl IntoIterator<Item: AsTask>, num_cpus: usize) -> Option<bool> {
+    let ts = collect(ts);
+
+    if !ts.iter().constrained() {
+        return None;
+    }
+
+    Some(ts.iter().enumerate().all(|(k, tk)| {
+        let Some(len) = window(tk) else {
+            return false;
+        };
+
+        let Some(slack) = len.checked_sub(tk.cost) else {
+            return false;
+        };
+
+        let work = ts.iter()
+                     .enumerate()
+                     .filter(|(i, _)| *i != k)
+                     .map(|(_, ti)| edf_workload(ti, len, 0))
+                     .collect::<Box<_>>();
+
+        let sum = work.iter().map(|w| (*w).min(slack)).sum::<Time>();
+        let cap = num_cpus as Time * slack;
+
+        sum < cap || (sum == cap && work.iter().any(|w| (1 ..= slack).contains(w)))
+    }))
+}
+
+/// Returns an upper bound on the workload of `task` within a window of length
+/// `len`, given that the response time of `task` is at most `resp`.
+pub(crate) fn carry_in(task: &Task, resp: Time, len: Time) -> Time {
+    let span = (len + resp).saturating_sub(task.cost);
+    let num = span / task.period;
+
+    num * task.cost + task.cost.min(span - num * task.period)
+}
+
+/// Computes a response-time bound for the task at index `k` of `ts`, measured from
+/// arrival, given bounds `resp` for every task and an additional `blocking` term
+/// to be divided among all CPUs; returns `None` if the bound exceeds its deadline.
+fn edf_response(
+    ts: &[Task],
+    k: usize,
+    resp: &[Time],
+    blocking: Time,
+    num_cpus: usize
+) -> Option<Time> {
+    let tk = &ts[k];
+    let len = window(tk)?;
+    let mut r = tk.cost;
+
+    loop {
+        let inter = ts.iter()
+                      .zip(resp)
+                      .enumerate()
+                      .filter(|(i, _)| *i != k)
+                      .map(|(_, (ti, ri))| {
+                          carry_in(ti, *ri, r)
+                          .min(edf_workload(ti, len, ti.deadline - ri))
+                          .min(r - tk.cost + 1)
+                      })
+                      .sum::<Time>();
+
+        let next = tk.cost + (inter + blocking) / num_cpus as Time;
+
+        if next > len {
+            return None;
+        } else if next == r {
+            return Some(r + tk.jitter);
+        }
+
+        r = next;
+    }
+}
+
+/// Tests task-set `ts` using Bertogna and Cirinei's response-time analysis for
+/// global EDF, as described in "Response-time analysis for globally scheduled
+/// symmetric multiprocessor platforms" (RTSS, 2007).
+///
+/// Response-time bounds start at each task's deadline and are iteratively
+/// refined, using the slack of each task to limit its interference on the others,
+/// until no bound changes.
+///
+/// Returns the result of the test if `ts` is constrained, otherwise `None`.
+pub fn rta(ts: impl IntoIterator<Item: AsTask>, num_cpus: usize) -> Option<bool> {
+    let ts = collect(ts);
+
+    if !ts.iter().constrained() {
+        return None;
+    }
+
+    Some(rta_blocking(&ts, &vec![0; ts.len()], num_cpus))
+}
+
+/// Runs the response-time analysis of [`rta`] on constrained task-set `ts`, adding
+/// `blocking[k]` to the total interference on the task at index `k`.
+pub(crate) fn rta_blocking(ts: &[Task], blocking: &[Time], num_cpus: usize) -> bool {
+    if ts.iter().any(|t| t.cost + t.jitter > t.deadline) {
+        return false;
+    } else if num_cpus == 0 {
+        return ts.iter().all(|t| t.cost == 0);
+    }
+
+    let mut resp = ts.iter().map(|t| t.deadline).collect::<Box<_>>();
+    let mut ok = vec![false; ts.len()];
+
+    loop {
+        let mut changed = false;
+
+        for k in 0 .. ts.len() {
+            if let Some(r) = edf_response(ts, k, &resp, blocking[k], num_cpus) {
+                ok[k] = true;
+
+                if r < resp[k] {
+                    resp[k] = r;
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    ok.into_iter().all(|x| x)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Dhall's example: two light tasks delay a heavy one, which then misses its
+    /// deadline under global EDF on two CPUs despite a total utilization of about `1.15`.
+    fn dhall() -> [Task; 3] {
+        [Task::new(2, 20), Task::new(2, 20), Task::new(20, 21)]
+    }
+
+    #[test]
+    fn dhall_effect() {
+        let ts = dhall();
+
+        assert!(!gfb(ts, 2));
+        assert_eq!(bak(ts, 2), Some(false));
+        assert_eq!(bcl(ts, 2), Some(false));
+        assert_eq!(rta(ts, 2), Some(false));
+    }
+
+    #[test]
+    fn light_load() {
+        let ts = [Task::new(1, 10); 4];
+
+        assert!(gfb(ts, 2));
+        assert_eq!(bak(ts, 2), Some(true));
+        assert_eq!(bcl(ts, 2), Some(true));
+        assert_eq!(rta(ts, 2), Some(true));
+    }
+
+    #[test]
+    fn gfb_bound() {
+        // the bound is 2 - 1/2 = 3/2 on two CPUs with a largest density of 1/2
+        assert!(gfb([Task::new(1, 2); 3], 2));
+        assert!(!gfb([Task::new(1, 2), Task::new(1, 2), Task::new(1, 2), Task::new(1, 10)], 2));
+    }
+
+    #[test]
+    fn arbitrary_deadlines() {
+        let ts = [Task::new(1, 4).with_deadline(6)];
+
+        assert!(gfb(ts, 1));
+        assert_eq!(bak(ts, 1), None);
+        assert_eq!(bcl(ts, 1), None);
+        assert_eq!(rta(ts, 1), None);
+    }
+
+    #[test]
+    fn no_cpus() {
+        let ts = [Task::new(1, 4)];
+
+        assert!(!gfb(ts, 0));
+        assert!(gfb([] as [Task; 0], 0));
+        assert_eq!(rta(ts, 0), Some(false));
+    }
+}
@@ -0,0 +1,246 @@
+//! Response-time analyses for global fixed-priority scheduling.
+//!
+//! All analyses in this module use [`Task::priority`] (lower values meaning higher
+//! priority, with ties interfering with each other) and require constrained deadlines.
+//! Blocking is accounted for by inflation: running an analysis on
+//! [`ObliviousTask`](`crate::sharing::ObliviousTask`)s adds the total blocking bound
+//! of each task to its cost.
+//!
+//! Preemption thresholds are not supported under global scheduling. Each analysis
+//! returns `None` if the task-set is not constrained or has a task with a
+//! [threshold](`Task::threshold`) above its priority, and otherwise a
+//! response-time bound for each task in the same order as the input, or `None` for
+//! tasks that cannot be shown to meet their deadline. Since lower-priority tasks
+//! assume that higher-priority ones meet their deadlines, once a task fails no bound
+//! is given for any task with the same or lower priority. Response times are measured
+//! from the arrival of each job, i.e. they include its release jitter.
+
+use super::{collect, gedf::carry_in};
+
+use crate::task::{Task, AsTask, Set, Time};
+
+/// Tests whether task-set `ts` can be analyzed by the analyses in this module, i.e. whether
+/// it is constrained and has no preemption thresholds.
+pub(crate) fn supported(ts: &[Task]) -> bool {
+    ts.iter().constrained() && ts.iter().all(|t| t.effective_threshold() == t.priority)
+}
+
+/// Runs the response-time analysis described by `response` on each task of `ts`,
+/// in priority order.
+///
+/// `response` is given the index of the task under analysis, the indices of the
+/// tasks that may interfere with it and the current response-time bounds of all tasks,
+/// and returns the fixed point for the task or `None` if it exceeds its deadline.
+pub(crate) fn analyze(
+    ts: &[Task],
+    response: impl Fn(usize, &[usize], &[Time]) -> Option<Time>
+) -> Box<[Option<Time>]> {
+    let mut order = (0 .. ts.len()).collect::<Box<_>>();
+    order.sort_by_key(|i| ts[*i].priority);
+
+    // tasks that are not yet analyzed are assumed to meet their deadline
+    let mut resp = ts.iter().map(|t| t.deadline).collect::<Box<_>>();
+    let mut out = vec![None; ts.len()].into_boxed_slice();
+
+    for group in order.chunk_by(|i, j| ts[*i].priority == ts[*j].priority) {
+        let mut failed = false;
+
+        for &k in group {
+            let hp = (0 .. ts.len()).filter(|i| *i != k && ts[*i].priority <= ts[k].priority)
+                                    .collect::<Box<_>>();
+
+            match response(k, &hp, &resp) {
+                Some(r) => {
+                    resp[k] = r;
+                    out[k] = Some(r);
+                },
+                None => failed = true
+            }
+        }
+
+        if failed {
+            for &k in group {
+                out[k] = None;
+            }
+
+            break;
+        }
+    }
+
+    out
+}
+
+/// Iterates `step` starting from the cost of `task` until it reaches a fixed point,
+/// which is a bound on the response time measured from release; returns the bound
+/// measured from arrival, or `None` if it exceeds the deadline of `task`.
+pub(crate) fn fixed_point(task: &Task, step: impl Fn(Time) -> Time) -> Option<Time> {
+    let mut r = task.cost;
+    let len = task.deadline.checked_sub(task.jitter)?;
+
+    if r > len {
+        return None;
+    }
+
+    loop {
+        let next = step(r);
+
+        if next > len {
+            return None;
+        } else if next == r {
+            return Some(r + task.jitter);
+        }
+
+        r = next;
+    }
+}
+
+/// Bertogna and Cirinei's response-time analysis for global fixed-priority scheduling,
+/// as described in "Response-time analysis for globally scheduled symmetric
+/// multiprocessor platforms" (RTSS, 2007).
+pub fn rta(ts: impl IntoIterator<Item: AsTask>, num_cpus: usize) -> Option<Box<[Option<Time>]>> {
+    let ts = collect(ts);
+
+    if !supported(&ts) {
+        return None;
+    } else if num_cpus == 0 {
+        return Some(vec![None; ts.len()].into());
+    }
+
+    Some(analyze(&ts, |k, hp, resp| {
+        let tk = &ts[k];
+
+        fixed_point(tk, |r| {
+            let inter = hp.iter()
+                          .map(|i| carry_in(&ts[*i], resp[*i], r).min(r - tk.cost + 1))
+                          .sum::<Time>();
+
+            tk.cost + inter / num_cpus as Time
+        })
+    }))
+}
+
+/// Returns an upper bound on the workload of `task` within a window of length `len`
+/// if no job of `task` is carried into the window.
+///
+/// Jobs that arrived before the window may still be released within it, so the
+/// window is extended backwards by the release jitter of `task`.
+fn nc_workload(task: &Task, len: Time) -> Time {
+    let len = len + task.jitter;
+    let num = len / task.period;
+
+    num * task.cost + task.cost.min(len - num * task.period)
+}
+
+/// Returns an upper bound on the workload of `task` within a window of length `len`
+/// if a job of `task` is carried into the window, given that the response time of
+/// `task` is at most `resp`.
+fn ci_workload(task: &Task, resp: Time, len: Time) -> Time {
+    let span = len.saturating_sub(task.cost);
+    let num = span / task.period;
+
+    let alpha = (span - num * task.period)
+                .saturating_sub(task.period - resp)
+                .min(task.cost.saturating_sub(1));
+
+    num * task.cost + task.cost + alpha
+}
+
+/// Guan, Stigge, Yi and Yu's improved response-time analysis for global fixed-priority
+/// scheduling (RTA-LC), as described in "New response time bounds for fixed priority
+/// multiprocessor scheduling" (RTSS, 2009).
+///
+/// This analysis exploits the fact that at most `num_cpus - 1` higher-priority tasks
+/// can have a job carried into the busy period of the task under analysis, and
+/// dominates [`rta`].
+pub fn rta_lc(ts: impl IntoIterator<Item: AsTask>, num_cpus: usize) -> Option<Box<[Option<Time>]>> {
+    let ts = collect(ts);
+
+    if !supported(&ts) {
+        return None;
+    }
+
+    Some(rta_lc_blocking(&ts, &vec![0; ts.len()], num_cpus))
+}
+
+/// Runs the response-time analysis of [`rta_lc`] on constrained task-set `ts`, adding
+/// `blocking[k]` to the total interference on the task at index `k`.
+pub(crate) fn rta_lc_blocking(ts: &[Task], blocking: &[Time], num_cpus: usize) -> Box<[Option<Time>]> {
+    if num_cpus == 0 {
+        return vec![None; ts.len()].into();
+    }
+
+    analyze(ts, |k, hp, resp| {
+        let tk = &ts[k];
+
+        fixed_point(tk, |r| {
+            let cap = r - tk.cost + 1;
+            let mut diff = Vec::with_capacity(hp.len());
+            let mut inter = blocking[k];
+
+            for &i in hp {
+                let nc = nc_workload(&ts[i], r).min(cap);
+                let ci = ci_workload(&ts[i], resp[i], r).min(cap);
+
+                inter += nc;
+                diff.push(ci.saturating_sub(nc));
+            }
+
+            diff.sort_unstable_by(|a, b| b.cmp(a));
+            inter += diff.iter().take(num_cpus - 1).sum::<Time>();
+
+            tk.cost + inter / num_cpus as Time
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(cost: Time, period: Time, priority: u64) -> Task {
+        Task { priority, ..Task::new(cost, period) }
+    }
+
+    #[test]
+    fn single_cpu() {
+        // Buttazzo's example, whose exact uniprocessor response times are 1, 3 and 10
+        let ts = [
+            task(1, 4, 0),
+            task(2, 6, 1),
+            task(3, 12, 2)
+        ];
+
+        let lc = rta_lc(ts, 1).unwrap();
+        assert_eq!(*lc, [Some(1), Some(3), Some(10)]);
+
+        for (a, b) in rta(ts, 1).unwrap().iter().zip(&lc) {
+            assert!(a.is_none() || a >= b);
+        }
+    }
+
+    #[test]
+    fn dhall_effect() {
+        let ts = [
+            task(2, 20, 0),
+            task(2, 20, 0),
+            task(20, 21, 1)
+        ];
+
+        assert_eq!(*rta(ts, 2).unwrap(), [Some(2), Some(2), None]);
+        assert_eq!(*rta_lc(ts, 2).unwrap(), [Some(2), Some(2), None]);
+    }
+
+    #[test]
+    fn no_cpus() {
+        assert_eq!(*rta([Task::new(1, 4)], 0).unwrap(), [None]);
+        assert_eq!(*rta_lc([Task::new(1, 4)], 0).unwrap(), [None]);
+    }
+
+    #[test]
+    fn thresholds() {
+        let ts = [task(1, 4, 0), task(2, 6, 1).with_threshold(0)];
+
+        assert_eq!(rta(ts, 2), None);
+        assert_eq!(rta_lc(ts, 2), None);
+    }
+}
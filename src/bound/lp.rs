@@ -0,0 +1,181 @@
+//! Tests for limited-preemptive scheduling.
+//!
+//! Each task may execute a _non-preemptive region_ of bounded length, during which
+//! it cannot be preempted by higher-priority jobs, which are then blocked. The longest
+//! non-preemptive region of each task is given to the tests in this module as
+//! `regions`, usually computed by [`regions`] from [`Task::npr`] and from the arrival
+//! bounds of a protocol with non-preemptive execution, such as the spin-based
+//! [`FlexibleMulti`](`crate::proto::FlexibleMulti`).
+//!
+//! On multiprocessors, a job is assumed to be blocked by lower-priority
+//! non-preemptive regions only upon its arrival, i.e. a job is never preempted in
+//! favor of a higher-priority one while a lower-priority job executes non-preemptively,
+//! as with Block et al.'s link-based scheduling from "A flexible real-time locking
+//! protocol for multiprocessors" (RTCSA, 2007); it is then blocked by at most one
+//! region on each CPU.
+
+use super::{collect, uni, gedf, gfp};
+
+use crate::{
+    task::{Task, AsTask, Set, Time, Policy},
+    sharing::ObliviousData
+};
+
+/// Returns the longest non-preemptive region of each task of `ts`, i.e. the larger of
+/// its [`npr`](`Task::npr`) and, if `data` is given, its arrival bound.
+///
+/// Protocols that already add arrival blocking to their total bounds, such as
+/// [`SingleClusterOm`](`crate::proto::SingleClusterOm`), should not be given as `data`,
+/// as their arrival blocking would be accounted for twice.
+///
+/// # Panics
+///
+/// Panics if `data` is given and its length differs from that of `ts`.
+pub fn regions(ts: &[Task], data: Option<&[ObliviousData]>) -> Box<[Time]> {
+    if let Some(d) = data {
+        assert_eq!(d.len(), ts.len());
+    }
+
+    ts.iter().enumerate().map(|(i, t)| {
+        t.npr.max(data.map_or(0, |d| d[i].arrival.length))
+    }).collect()
+}
+
+/// Returns the blocking due to non-preemptive regions of each task of `ts` on
+/// `num_cpus` CPUs under `policy`, i.e. the sum of the `num_cpus` longest `regions`
+/// of tasks that `policy` does not allow to [dominate](`Policy::dominates`) it.
+///
+/// # Panics
+///
+/// Panics if the lengths of `ts` and `regions` differ.
+pub fn blocking(ts: &[Task], regions: &[Time], policy: Policy, num_cpus: usize) -> Box<[Time]> {
+    assert_eq!(regions.len(), ts.len());
+
+    (0 .. ts.len()).map(|k| {
+        let mut lp = (0 .. ts.len()).filter(|j| *j != k && !policy.dominates(&ts[*j], &ts[k]))
+                                    .map(|j| regions[j])
+                                    .collect::<Box<_>>();
+
+        lp.sort_unstable_by(|a, b| b.cmp(a));
+        lp.iter().take(num_cpus).sum()
+    }).collect()
+}
+
+/// Response-time analysis for uniprocessor limited-preemptive fixed-priority scheduling,
+/// using [`uni::fp`] with each task blocked by the longest region of a lower-priority task.
+///
+/// # Panics
+///
+/// Panics if the lengths of `ts` and `regions` differ.
+pub fn fp(ts: impl IntoIterator<Item: AsTask>, regions: &[Time]) -> Box<[Option<Time>]> {
+    let ts = collect(ts);
+    let block = blocking(&ts, regions, Policy::Fixed, 1);
+
+    uni::fp(ts, Some(&block))
+}
+
+/// Sufficient test for uniprocessor limited-preemptive EDF, using [`uni::edf`] with each
+/// task blocking tasks with shorter relative deadlines for the length of its longest region.
+///
+/// # Panics
+///
+/// Panics if the lengths of `ts` and `regions` differ.
+pub fn edf(ts: impl IntoIterator<Item: AsTask>, regions: &[Time]) -> bool {
+    let ts = collect(ts);
+    assert_eq!(regions.len(), ts.len());
+
+    uni::edf(ts, Some(regions))
+}
+
+/// Response-time analysis for global limited-preemptive fixed-priority scheduling, using
+/// [`gfp::rta_lc`] with the blocking given by [`blocking`] added to the interference on
+/// each task.
+///
+/// Returns `None` if `ts` is not constrained or has preemption thresholds, as for
+/// [`gfp::rta_lc`].
+///
+/// # Panics
+///
+/// Panics if the lengths of `ts` and `regions` differ.
+pub fn global_fp(
+    ts: impl IntoIterator<Item: AsTask>,
+    regions: &[Time],
+    num_cpus: usize
+) -> Option<Box<[Option<Time>]>> {
+    let ts = collect(ts);
+    let block = blocking(&ts, regions, Policy::Fixed, num_cpus);
+
+    gfp::supported(&ts).then(|| gfp::rta_lc_blocking(&ts, &block, num_cpus))
+}
+
+/// Tests task-set `ts` for global limited-preemptive EDF, using [`gedf::rta`] with the
+/// blocking given by [`blocking`] added to the interference on each task.
+///
+/// Returns the result of the test if `ts` is constrained, otherwise `None`.
+///
+/// # Panics
+///
+/// Panics if the lengths of `ts` and `regions` differ.
+pub fn global_edf(ts: impl IntoIterator<Item: AsTask>, regions: &[Time], num_cpus: usize) -> Option<bool> {
+    let ts = collect(ts);
+    let block = blocking(&ts, regions, Policy::Edf, num_cpus);
+
+    ts.iter().constrained().then(|| gedf::rta_blocking(&ts, &block, num_cpus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sharing::Bound;
+
+    /// Buttazzo's example of fixed-priority scheduling, with only the lowest-priority
+    /// task executing non-preemptively.
+    fn buttazzo() -> [Task; 3] {
+        [Task::new(1, 4),
+         Task { priority: 1, ..Task::new(2, 6) },
+         Task { priority: 2, ..Task::new(3, 10) }]
+    }
+
+    #[test]
+    fn regions_arrival() {
+        let ts = [Task::new(1, 4).with_npr(2), Task::new(1, 4).with_npr(1)];
+        let data = [ObliviousData::default(), ObliviousData { arrival: Bound::new(3), ..Default::default() }];
+
+        assert_eq!(regions(&ts, None).as_ref(), [2, 1]);
+        assert_eq!(regions(&ts, Some(&data)).as_ref(), [2, 3]);
+    }
+
+    #[test]
+    fn blocking_policies() {
+        let ts = buttazzo();
+
+        assert_eq!(blocking(&ts, &[1, 2, 3], Policy::Fixed, 1).as_ref(), [3, 3, 0]);
+        assert_eq!(blocking(&ts, &[1, 2, 3], Policy::Fixed, 2).as_ref(), [5, 3, 0]);
+        assert_eq!(blocking(&ts, &[1, 2, 3], Policy::Edf, 1).as_ref(), [3, 3, 0]);
+        assert_eq!(blocking(&ts, &[1, 2, 3], Policy::Fifo, 1).as_ref(), [0, 0, 0]);
+    }
+
+    #[test]
+    fn uniprocessor() {
+        let ts = buttazzo();
+
+        assert_eq!(fp(ts, &[0; 3]), uni::fp(ts, None));
+        assert_eq!(fp(ts, &[0, 0, 2]).as_ref(), [Some(3), Some(6), Some(10)]);
+        assert_eq!(fp(ts, &[0, 0, 3]).as_ref(), [Some(4), None, Some(10)]);
+
+        // the first task misses its deadline if blocked by a region of length 4
+        assert!(edf(ts, &[0, 0, 3]));
+        assert!(!edf(ts, &[0, 0, 4]));
+    }
+
+    #[test]
+    fn global() {
+        let ts = buttazzo();
+
+        assert_eq!(global_fp(ts, &[0; 3], 2), gfp::rta_lc(ts, 2));
+        assert_eq!(global_edf(ts, &[0; 3], 2), gedf::rta(ts, 2));
+        assert_eq!(global_fp([Task::new(1, 4).with_deadline(5)], &[0], 2), None);
+        assert_eq!(global_edf([Task::new(1, 4).with_deadline(5)], &[0], 2), None);
+    }
+}
@@ -0,0 +1,79 @@
+//! Schedulability bounds and tests.
+//!
+//! Tests for specific schedulers are grouped in submodules; all of them accept
+//! any iterator of [`AsTask`] items, so that they can be run on [`Task`]s as well
+//! as on tasks inflated by blocking, such as
+//! [`ObliviousTask`](`crate::sharing::ObliviousTask`)s.
+//!
+//! Each test either accounts for release jitter or does not accept task-sets with
+//! jitter, as documented for each. Release offsets are ignored by all tests except
+//! [`exact::periodic`], as every other test assumes the worst-case phasing of arrivals;
+//! see [`Task::offset`].
+
+pub mod exact;
+pub mod gedf;
+pub mod gfp;
+pub mod lp;
+pub mod pt;
+pub mod tardiness;
+pub mod uni;
+
+use crate::task::{Task, AsTask, Set};
+
+use num_order::NumOrd;
+
+/// Tests whether task-set `ts` has bounded response times under global EDF
+/// and/or global FIFO for a task-set with implicit deadlines.
+///
+/// Returns the result of [`bounded`] if `ts` is implicit, otherwise `None`.
+#[deprecated(note = "response times are bounded for any deadlines; use `bounded` instead")]
+pub fn soft(ts: impl Set + Clone, num_cpus: usize) -> Option<bool> {
+    ts.clone().implicit().then(|| bounded(ts, num_cpus))
+}
+
+/// Tests whether task-set `ts` has bounded response times under global EDF
+/// and/or global FIFO.
+///
+/// Both schedulers have the same condition for boundedness (though they do
+/// not in general have the same tardiness bounds), which only depends on
+/// utilization: response times are bounded for implicit, constrained and
+/// arbitrary deadlines alike, as shown by Leontyev and Anderson's "Generalized
+/// tardiness bounds for global multiprocessor scheduling" (Real-Time Systems, 2010).
+///
+/// Density plays no role here: a deadline shorter than the period makes jobs tardy
+/// earlier, but does not add any work, and every job still completes within a bounded
+/// time after its release as long as the long-term demand `U` does not exceed the
+/// capacity `m` and no single task needs more than a CPU. Deadlines only affect the
+/// bounds themselves, which are computed by [`tardiness`]; density-based tests for
+/// zero tardiness, such as [`gedf::gfb`], are in [`gedf`].
+pub fn bounded(ts: impl Set + Clone, num_cpus: usize) -> bool {
+    ts.clone().utilization().num_le(&num_cpus) && ts.feasible()
+}
+
+/// Collects the tasks that the items of `ts` are analyzed as.
+fn collect(ts: impl IntoIterator<Item: AsTask>) -> Box<[Task]> {
+    ts.into_iter()
+      .map(|t| t.as_task())
+      .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounded_any_deadline() {
+        let ts = [Task::new(2, 3), Task::new(2, 3).with_deadline(1), Task::new(2, 3).with_deadline(5)];
+
+        assert!(bounded(&ts, 2));
+        assert!(!bounded(&ts, 1));
+        assert!(!bounded(&[Task::new(4, 3)], 2));
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn soft_implicit_only() {
+        assert_eq!(soft(&[Task::new(2, 3); 3], 2), Some(true));
+        assert_eq!(soft(&[Task::new(2, 3).with_deadline(2)], 2), None);
+    }
+}
@@ -0,0 +1,206 @@
+//! Tests for uniprocessor fixed-priority scheduling with preemption thresholds.
+//!
+//! Each job runs at its task's priority until it starts executing, and then at its
+//! [`threshold`](`Task::threshold`), so that it can only be preempted by jobs of tasks
+//! with priority higher than or equal to it. Thresholds reduce the number of
+//! preemptions and, since tasks that cannot preempt each other never have jobs started
+//! at the same time, allow them to share a stack; in exchange, a job may be blocked by
+//! a lower-priority job with a high enough threshold.
+
+use super::collect;
+
+use crate::{
+    task::{Task, AsTask, Time},
+    rsrc::{System, RequestKind, Usage, RequestSet},
+    sharing::{ObliviousAnalyzer, ObliviousData}
+};
+
+use dashu::rational::RBig;
+
+/// Returns the blocking induced by preemption thresholds on each task of `ts`, i.e. the
+/// largest cost of a task with lower priority whose threshold is higher than the
+/// priority of the task, so that the task cannot preempt it.
+pub fn blocking(ts: &[Task]) -> Box<[Time]> {
+    ts.iter().map(|tk| {
+        ts.iter()
+          .filter(|tj| tj.priority > tk.priority && tj.effective_threshold() < tk.priority)
+          .map(|tj| tj.cost)
+          .max()
+          .unwrap_or_default()
+    }).collect()
+}
+
+/// Computes the response-time bound of the task at index `k` of `ts`, as for [`fp`],
+/// given the blocking `block` from lower-priority tasks.
+pub(crate) fn response(ts: &[Task], k: usize, block: Time) -> Option<Time> {
+    let tk = &ts[k];
+    let theta = tk.effective_threshold();
+
+    let hp = (0 .. ts.len()).filter(|i| *i != k && ts[*i].priority <= tk.priority)
+                            .collect::<Box<_>>();
+
+    // busy periods are only bounded if they cannot grow by more than they last
+    let util = hp.iter().chain([&k]).fold(RBig::ZERO, |acc, i| {
+        acc + RBig::from(ts[*i].cost) / RBig::from(ts[*i].period)
+    });
+
+    let idle = block == 0 && hp.iter().chain([&k]).all(|i| ts[*i].jitter == 0);
+
+    if util > RBig::ONE || (util == RBig::ONE && !idle) {
+        return None;
+    }
+
+    let fixed_point = |start: Time, step: &dyn Fn(Time) -> Time| {
+        let mut w = start;
+
+        loop {
+            let next = step(w);
+
+            if next == w {
+                return w;
+            }
+
+            w = next;
+        }
+    };
+
+    // the level-i busy period, including all jobs of the task under analysis
+    let busy = fixed_point(block + tk.cost, &|w| {
+        block + hp.iter().chain([&k]).map(|i| ts[*i].max_jobs(w) * ts[*i].cost).sum::<Time>()
+    });
+
+    let mut resp = 0;
+
+    for q in 0 .. tk.max_jobs(busy).max(1) {
+        // the job starts once every job released up to then with higher or equal priority
+        // has completed
+        let start = fixed_point(block + q * tk.cost, &|s| {
+            block + q * tk.cost + hp.iter().map(|i| ts[*i].max_jobs(s + 1) * ts[*i].cost).sum::<Time>()
+        });
+
+        // and is then only preempted by jobs released later with priority above its threshold
+        let finish = fixed_point(start + tk.cost, &|f| {
+            start + tk.cost + hp.iter().filter(|i| ts[**i].priority <= theta).map(|i| {
+                ts[*i].max_jobs(f).saturating_sub(ts[*i].max_jobs(start + 1)) * ts[*i].cost
+            }).sum::<Time>()
+        });
+
+        let r = (finish + tk.jitter).saturating_sub(q * tk.period);
+
+        if r > tk.deadline {
+            return None;
+        }
+
+        resp = resp.max(r);
+    }
+
+    Some(resp)
+}
+
+/// Response-time analysis for uniprocessor fixed-priority scheduling with preemption
+/// thresholds, after Wang and Saksena's "Scheduling fixed-priority tasks with preemption
+/// threshold" (RTCSA, 1999), with Regehr's correction from "Scheduling tasks with mixed
+/// preemption relations for robustness to timing faults" (RTSS, 2002): each job of the
+/// level-i busy period is analyzed separately up to its start and then up to its
+/// completion.
+///
+/// Priorities are as for [`uni::fp`](`super::uni::fp`), while a started job is preempted
+/// by jobs of tasks whose priority equals its threshold. Each task is blocked by the larger of its induced
+/// [`blocking`] and `blocking`, if given, which is assumed to be due to a protocol such
+/// as Baker's SRP under which a job is blocked by at most one lower-priority job. With
+/// no thresholds, this is the same analysis as [`uni::fp`](`super::uni::fp`).
+///
+/// Returns a bound on the response time of each task, measured from its arrival, or
+/// `None` for tasks that can miss their deadline or whose busy period is unbounded.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn fp(ts: impl IntoIterator<Item: AsTask>, blocking: Option<&[Time]>) -> Box<[Option<Time>]> {
+    let ts = collect(ts);
+
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), ts.len());
+    }
+
+    let induced = self::blocking(&ts);
+
+    (0 .. ts.len()).map(|k| {
+        response(&ts, k, induced[k].max(blocking.map_or(0, |b| b[k])))
+    }).collect()
+}
+
+/// Runs analyzer `an` on system `sys` and returns its results for each task, together
+/// with the blocking induced on the task by the preemption thresholds of `sys`.
+///
+/// Under protocols where a job is blocked by at most one lower-priority job upon its
+/// arrival, the larger of the arrival bound and the induced blocking of each task can be
+/// given as blocking to [`fp`].
+pub fn run<K, A>(sys: &System<'_, K>, an: &A) -> Box<[(ObliviousData, Time)]>
+where K: RequestKind,
+      A: ObliviousAnalyzer<K> + ?Sized,
+      for<'x> Usage<'x, K>: RequestSet {
+    sys.run::<A>(an)
+       .iter()
+       .copied()
+       .zip(blocking(sys.tasks()))
+       .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bound::uni;
+
+    /// Wang and Saksena's example, with thresholds given as `[t1, t2, t3]`.
+    fn wang_saksena(thresholds: [u64; 3]) -> [Task; 3] {
+        let ts = [
+            Task { priority: 1, ..Task::new(20, 70).with_deadline(50) },
+            Task { priority: 2, ..Task::new(20, 80) },
+            Task { priority: 3, ..Task::new(35, 200).with_deadline(100) }
+        ];
+
+        [0, 1, 2].map(|k| ts[k].with_threshold(thresholds[k]))
+    }
+
+    #[test]
+    fn no_thresholds() {
+        let ts = wang_saksena([u64::MAX; 3]);
+
+        assert_eq!(*blocking(&ts), [0, 0, 0]);
+        assert_eq!(fp(ts, None), uni::fp(ts, None));
+        assert_eq!(*fp(ts, None), [Some(20), Some(40), None]);
+    }
+
+    #[test]
+    fn non_preemptive() {
+        let ts = wang_saksena([0; 3]);
+
+        assert_eq!(*blocking(&ts), [35, 35, 0]);
+        assert_eq!(*fp(ts, None), [None, Some(75), Some(75)]);
+    }
+
+    #[test]
+    fn wang_saksena_thresholds() {
+        // the second task cannot be preempted, the third only by the first
+        let ts = wang_saksena([1, 0, 1]);
+
+        assert_eq!(*blocking(&ts), [20, 35, 0]);
+        assert_eq!(*fp(ts, None), [Some(40), Some(75), Some(95)]);
+
+        // a job is preempted by tasks whose priority equals its threshold
+        let ts = wang_saksena([1, 1, 1]);
+        assert_eq!(*fp(ts, None), [Some(20), None, Some(95)]);
+    }
+
+    #[test]
+    fn external_blocking() {
+        let ts = wang_saksena([1, 0, 1]);
+
+        // external blocking only counts where it exceeds the induced one
+        assert_eq!(*fp(ts, Some(&[10, 10, 0])), [Some(40), Some(75), Some(95)]);
+        assert_eq!(*fp(ts, Some(&[25, 0, 0])), [Some(45), Some(75), Some(95)]);
+        assert_eq!(*fp(ts, Some(&[0, 0, 10])), [Some(40), Some(75), None]);
+    }
+}
@@ -0,0 +1,186 @@
+//! Tardiness bounds for soft real-time global scheduling.
+//!
+//! All bounds in this module are computed exactly as [`RBig`] rationals and are
+//! returned for each task in the same order as the input; the response time of
+//! each task is bounded by its deadline plus its tardiness bound. Deadlines may be
+//! implicit, constrained or arbitrary. Bounds are only computed for task-sets that
+//! have bounded tardiness according to [`bounded`](`super::bounded`) and no release jitter.
+
+use super::collect;
+
+use crate::task::{Task, AsTask, Set};
+
+use dashu::{integer::UBig, rational::RBig};
+use num_order::NumOrd;
+
+/// Returns the utilization of `task` as a canonical rational.
+fn util(task: &Task) -> RBig {
+    RBig::from(task.cost) / RBig::from(task.period)
+}
+
+/// Returns the sum of the `num` largest values in `values`.
+fn largest(values: impl IntoIterator<Item = RBig>, num: usize) -> RBig {
+    let mut values = values.into_iter().collect::<Box<_>>();
+    values.sort_unstable_by(|a, b| b.cmp(a));
+
+    values.iter()
+          .take(num)
+          .fold(RBig::ZERO, |acc, x| acc + x)
+}
+
+/// Tests whether `ts` has bounded tardiness on `num_cpus` CPUs and can be analyzed,
+/// i.e. has no release jitter and at least one CPU.
+fn bounded(ts: &[Task], num_cpus: usize) -> bool {
+    num_cpus > 0
+    && ts.iter().utilization().num_le(&num_cpus)
+    && ts.iter().feasible()
+    && ts.iter().all(|t| t.jitter == 0)
+}
+
+/// Tardiness bound for global EDF.
+///
+/// Global EDF is analyzed as a G-EL scheduler with priority points at job deadlines,
+/// as described in Leontyev and Anderson's "Generalized tardiness bounds for global
+/// multiprocessor scheduling" (Real-Time Systems, 2010). If `ts` is implicit, each
+/// bound is also capped by Devi and Anderson's bound from "Tardiness bounds under
+/// global EDF scheduling on a multiprocessor" (Real-Time Systems, 2008).
+///
+/// Returns the bounds if `ts` has bounded tardiness and no jitter, otherwise `None`.
+pub fn edf(ts: impl IntoIterator<Item: AsTask>, num_cpus: usize) -> Option<Box<[RBig]>> {
+    let ts = collect(ts);
+
+    if !bounded(&ts, num_cpus) {
+        return None;
+    }
+
+    let points = ts.iter().map(|t| RBig::from(t.deadline)).collect::<Box<_>>();
+    let s = gel(&ts, &points, num_cpus);
+    let gel = ts.iter().map(|t| &s + RBig::from(t.cost));
+
+    if !ts.iter().implicit() {
+        return Some(gel.collect());
+    }
+
+    let total = ts.iter().map(util).fold(RBig::ZERO, |acc, u| acc + u);
+
+    // number of tasks that can be tardy at once, i.e. ceil(total) - 1
+    let lambda = usize::try_from(UBig::try_from(total.ceil()).unwrap())
+                 .unwrap()
+                 .saturating_sub(1);
+
+    let costs = largest(ts.iter().map(|t| RBig::from(t.cost)), lambda);
+    let utils = largest(ts.iter().map(util), lambda.saturating_sub(1));
+    let min_cost = RBig::from(ts.iter().map(|t| t.cost).min().unwrap_or_default());
+
+    let x = ((costs - min_cost) / (RBig::from(num_cpus) - utils)).max(RBig::ZERO);
+
+    Some(ts.iter().zip(gel).map(|(t, g)| (&x + RBig::from(t.cost)).min(g)).collect())
+}
+
+/// Solves the response-time bound of a G-EL scheduler, i.e. a scheduler that
+/// prioritizes each job by its release time plus a per-task _relative priority point_,
+/// given in `points` for each task of `ts`.
+///
+/// Returns the smallest `s` such that the response time of each task is at most
+/// its priority point plus `s` plus its cost, as derived from Leontyev and Anderson's
+/// "Generalized tardiness bounds for global multiprocessor scheduling" (Real-Time
+/// Systems, 2010) in the form of Erickson, Anderson and Ward's compliant vectors.
+///
+/// `ts` must have bounded tardiness on `num_cpus` CPUs.
+fn gel(ts: &[Task], points: &[RBig], num_cpus: usize) -> RBig {
+    // work that may be pending at a priority point, beyond that of the current job
+    let pending = ts.iter().zip(points).map(|(t, y)| {
+        (RBig::from(t.cost) * (RBig::ONE - y / RBig::from(t.period))).max(RBig::ZERO)
+    }).collect::<Box<_>>();
+
+    let total = pending.iter().fold(RBig::ZERO, |acc, x| acc + x);
+    let mut s = RBig::ZERO;
+
+    // the fixed point is the maximum over subsets of m - 1 tasks, which is
+    // reached by always picking the largest terms for the current value
+    loop {
+        let mut terms = ts.iter().zip(&pending).map(|(t, p)| {
+            let base = RBig::from(t.cost) - p;
+            (&s * util(t) + &base, base, util(t))
+        }).collect::<Box<_>>();
+
+        terms.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+
+        let (base, utils) = terms.iter()
+                                 .take(num_cpus.saturating_sub(1))
+                                 .fold((total.clone(), RBig::ZERO), |(b, u), t| (b + &t.1, u + &t.2));
+
+        let next = base / (RBig::from(num_cpus) - utils);
+
+        if next <= s {
+            return s;
+        }
+
+        s = next;
+    }
+}
+
+/// Leontyev and Anderson's tardiness bound for global FIFO, obtained from the analysis
+/// of G-EL schedulers with priority points at job releases; see "Tardiness bounds for
+/// FIFO scheduling on multiprocessors" (ECRTS, 2007).
+///
+/// Since priority points do not depend on deadlines, the bound on response times is the
+/// same for any deadlines, and tardiness is whatever part of it exceeds the deadline.
+///
+/// Returns the bounds if `ts` has bounded tardiness and no jitter, otherwise `None`.
+pub fn fifo(ts: impl IntoIterator<Item: AsTask>, num_cpus: usize) -> Option<Box<[RBig]>> {
+    let ts = collect(ts);
+
+    if !bounded(&ts, num_cpus) {
+        return None;
+    }
+
+    let s = gel(&ts, &vec![RBig::ZERO; ts.len()], num_cpus);
+
+    Some(ts.iter().map(|t| {
+        (&s + RBig::from(t.cost) - RBig::from(t.deadline)).max(RBig::ZERO)
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn devi_anderson() {
+        // three tasks of utilization 2/3 fully load two CPUs; Devi and Anderson's bound
+        // is (2 - 2) / 2 + 2 = 2, below the G-EL bound of 3/2 + 2
+        let ts = [Task::new(2, 3); 3];
+
+        assert_eq!(*edf(ts, 2).unwrap(), [RBig::from(2), RBig::from(2), RBig::from(2)]);
+    }
+
+    #[test]
+    fn fifo_uniprocessor() {
+        // on one CPU, the response time of each task is bounded by the total cost of all
+        // tasks plus its own cost, i.e. 4 and 5
+        let ts = [Task::new(1, 4), Task::new(2, 6)];
+
+        assert_eq!(*fifo(ts, 1).unwrap(), [RBig::ZERO, RBig::ZERO]);
+        assert_eq!(*fifo(ts.map(|t| t.with_deadline(2)), 1).unwrap(), [RBig::from(2), RBig::from(3)]);
+    }
+
+    #[test]
+    fn unbounded() {
+        assert!(edf([Task::new(3, 4); 3], 2).is_none());
+        assert!(fifo([Task::new(5, 4)], 2).is_none());
+        assert!(edf([Task::new(1, 4).with_jitter(1)], 1).is_none());
+        assert!(edf([Task::new(1, 4)], 0).is_none());
+        assert!(fifo([Task::new(1, 4)], 0).is_none());
+    }
+
+    #[test]
+    fn constrained_deadlines() {
+        // on one CPU, G-EL with priority points at deadlines bounds tardiness by the
+        // pending work at a priority point, 1 * (1 - 2/4) + 2 * (1 - 3/6) = 3/2, plus the cost
+        let ts = [Task::new(1, 4).with_deadline(2), Task::new(2, 6).with_deadline(3)];
+        let half = RBig::ONE / RBig::from(2);
+
+        assert_eq!(*edf(ts, 1).unwrap(), [&half * RBig::from(5), &half * RBig::from(7)]);
+    }
+}
@@ -0,0 +1,324 @@
+//! Exact tests for uniprocessor scheduling.
+//!
+//! Unlike the tests for global scheduling, the tests in this module support
+//! arbitrary deadlines. They are the building blocks for partitioned scheduling,
+//! where they are run on the tasks assigned to each CPU.
+
+use super::{collect, pt};
+
+use crate::task::{Task, AsTask, Time};
+
+use dashu::{integer::IBig, rational::RBig};
+
+/// Demand-bound function of task-set `ts` for an interval of length `t`, i.e. the
+/// total cost of the jobs that have both release and deadline within the interval.
+pub(crate) fn dbf(ts: &[Task], t: Time) -> Time {
+    ts.iter()
+      .filter(|task| t >= task.deadline)
+      .map(|task| ((t - task.deadline) / task.period + 1) * task.cost)
+      .sum()
+}
+
+/// Returns the largest absolute deadline of a job of `ts` released synchronously
+/// at time `0` that is strictly less than `t`, if any.
+pub(crate) fn prev_deadline(ts: &[Task], t: Time) -> Option<Time> {
+    ts.iter()
+      .filter(|task| t > task.deadline)
+      .map(|task| (t - 1 - task.deadline) / task.period * task.period + task.deadline)
+      .max()
+}
+
+/// Returns the length of the synchronous busy period of `ts`, which must have
+/// utilization at most `1`.
+fn busy_period(ts: &[Task]) -> Time {
+    let mut w = ts.iter().map(|t| t.cost).sum::<Time>();
+
+    loop {
+        let next = ts.iter()
+                     .map(|t| w.div_ceil(t.period) * t.cost)
+                     .sum();
+
+        if next == w {
+            return w;
+        }
+
+        w = next;
+    }
+}
+
+/// Exact processor-demand test for uniprocessor EDF, using Zhang and Burns'
+/// _Quick Processor-demand Analysis_ (QPA) as described in "Schedulability analysis
+/// for real-time systems with EDF scheduling" (IEEE TC, 2009).
+///
+/// Release jitter is accounted for exactly by shortening the deadline of each job by
+/// the jitter of its task, since a job released as late as possible has the least time
+/// to meet its deadline.
+///
+/// If `blocking` is given, it must contain a term for each task of `ts`: the longest
+/// time for which that task can block tasks with a shorter relative deadline, such as
+/// its longest critical section under the _Stack Resource Policy_ (SRP). Blocking is
+/// then accounted for as in Baruah's "Resource sharing in EDF-scheduled systems"
+/// (RTSS, 2006), by checking every deadline up to the largest relative deadline
+/// exhaustively.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn edf(ts: impl IntoIterator<Item: AsTask>, blocking: Option<&[Time]>) -> bool {
+    let orig = collect(ts);
+
+    if orig.iter().any(|t| t.cost + t.jitter > t.deadline) {
+        return false;
+    }
+
+    // the demand of each task is that of a task without jitter and a shorter deadline
+    let ts = orig.iter()
+                 .map(|t| Task { deadline: t.deadline - t.jitter, jitter: 0, ..*t })
+                 .collect::<Box<_>>();
+
+    let util = ts.iter().fold(RBig::ZERO, |acc, t| {
+        acc + RBig::from(t.cost) / RBig::from(t.period)
+    });
+
+    if util > RBig::ONE {
+        return false;
+    }
+
+    let Some(max_dl) = orig.iter().map(|t| t.deadline).max() else {
+        return true;
+    };
+
+    let mut limit = busy_period(&ts);
+
+    if util < RBig::ONE {
+        let slack = ts.iter().fold(RBig::ZERO, |acc, t| {
+            let diff = IBig::from(t.period) - IBig::from(t.deadline);
+            acc + RBig::from(diff) * RBig::from(t.cost) / RBig::from(t.period)
+        }) / (RBig::ONE - util);
+
+        let bound = Time::try_from(slack.ceil()).unwrap_or_default().max(max_dl);
+        limit = limit.min(bound);
+    }
+
+    // below the largest relative deadline, blocking must be checked exhaustively
+    let low = match blocking {
+        Some(b) => {
+            assert_eq!(b.len(), ts.len());
+            max_dl
+        },
+        None => ts.iter().map(|t| t.deadline).min().unwrap_or_default()
+    };
+
+    if let Some(mut t) = prev_deadline(&ts, limit) {
+        loop {
+            let demand = dbf(&ts, t);
+
+            if demand > t {
+                return false;
+            } else if demand <= low {
+                break;
+            }
+
+            t = if demand < t {
+                demand
+            } else if let Some(d) = prev_deadline(&ts, t) {
+                d
+            } else {
+                break;
+            };
+        }
+    }
+
+    let Some(blocking) = blocking else {
+        return true;
+    };
+
+    let mut next = prev_deadline(&ts, max_dl + 1);
+
+    while let Some(t) = next {
+        // a job blocks an interval only if its deadline, measured from its arrival,
+        // falls after the end of the interval
+        let block = orig.iter()
+                        .zip(blocking)
+                        .filter(|(task, _)| task.deadline > t)
+                        .map(|(_, b)| *b)
+                        .max()
+                        .unwrap_or_default();
+
+        if dbf(&ts, t) + block > t {
+            return false;
+        }
+
+        next = prev_deadline(&ts, t);
+    }
+
+    true
+}
+
+/// Exact response-time analysis for uniprocessor fixed-priority scheduling, after
+/// Joseph and Pandya's "Finding response times in a real-time system" (The Computer
+/// Journal, 1986) and Audsley et al.'s "Applying new scheduling theory to static
+/// priority pre-emptive scheduling" (Software Engineering Journal, 1993).
+///
+/// Priorities are given by [`Task::priority`], with lower values meaning higher
+/// priority and ties interfering with each other. If given, `blocking` contains the
+/// longest time for which each task can be blocked by lower-priority tasks. Preemption
+/// thresholds are only accounted for by the [blocking](`super::pt::blocking`) they
+/// induce, which is combined with `blocking` as in [`pt::fp`](`super::pt::fp`); the
+/// latter also exploits the reduced interference due to thresholds. Release
+/// jitter is accounted for as in Audsley et al. Arbitrary deadlines are supported by
+/// examining every job in the level-i busy period, as in Tindell, Burns and Wellings'
+/// "An extendible approach for analyzing fixed priority hard real-time tasks"
+/// (Real-Time Systems, 1994).
+///
+/// Returns a bound on the response time of each task, measured from its arrival, in
+/// the same order as the input, or `None` for tasks that can miss their deadline.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn fp(ts: impl IntoIterator<Item: AsTask>, blocking: Option<&[Time]>) -> Box<[Option<Time>]> {
+    let ts = collect(ts);
+
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), ts.len());
+    }
+
+    let blocking = pt::blocking(&ts).iter()
+                                    .enumerate()
+                                    .map(|(k, b)| (*b).max(blocking.map_or(0, |b| b[k])))
+                                    .collect::<Box<_>>();
+
+    (0 .. ts.len()).map(|k| {
+        let tk = &ts[k];
+        let hp = (0 .. ts.len()).filter(|i| *i != k && ts[*i].priority <= tk.priority)
+                                .collect::<Box<_>>();
+
+        let mut resp = 0;
+
+        for q in 0 .. {
+            // completion of the (q + 1)-th job in the busy period
+            let mut w = blocking[k] + (q + 1) * tk.cost;
+
+            loop {
+                let next = blocking[k] + (q + 1) * tk.cost + hp.iter().map(|i| {
+                    ts[*i].max_jobs(w) * ts[*i].cost
+                }).sum::<Time>();
+
+                if (next + tk.jitter).saturating_sub(q * tk.period) > tk.deadline {
+                    return None;
+                } else if next == w {
+                    break;
+                }
+
+                w = next;
+            }
+
+            resp = resp.max(w + tk.jitter - q * tk.period);
+
+            if w + tk.jitter <= (q + 1) * tk.period {
+                break;
+            }
+        }
+
+        Some(resp)
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Checks the demand of `ts` at every point within twice the product of the periods,
+    /// a multiple of the hyperperiod, plus the largest deadline, which is exact for
+    /// synchronous releases without jitter.
+    fn brute_edf(ts: &[Task]) -> bool {
+        let hyper = ts.iter().map(|t| t.period).product::<Time>();
+        let max_dl = ts.iter().map(|t| t.deadline).max().unwrap();
+
+        (1 ..= 2 * hyper + max_dl).all(|t| dbf(ts, t) <= t)
+    }
+
+    #[test]
+    fn edf_full_utilization() {
+        assert!(edf([Task::new(2, 4), Task::new(3, 6)], None));
+        assert!(!edf([Task::new(2, 4), Task::new(4, 6)], None));
+    }
+
+    #[test]
+    fn edf_constrained() {
+        let sets = [
+            vec![Task::new(1, 4).with_deadline(2), Task::new(2, 6).with_deadline(3)],
+            vec![Task::new(2, 4).with_deadline(2), Task::new(1, 6).with_deadline(2)],
+            vec![Task::new(2, 5).with_deadline(3), Task::new(2, 7).with_deadline(4), Task::new(3, 10).with_deadline(8)],
+            vec![Task::new(1, 3).with_deadline(2), Task::new(2, 5).with_deadline(4), Task::new(2, 10).with_deadline(9)],
+            vec![Task::new(3, 8).with_deadline(5), Task::new(3, 12).with_deadline(6), Task::new(4, 10).with_deadline(10)]
+        ];
+
+        for ts in sets {
+            assert_eq!(edf(&ts, None), brute_edf(&ts));
+        }
+    }
+
+    #[test]
+    fn edf_jitter() {
+        assert!(edf([Task::new(2, 4).with_deadline(3).with_jitter(1)], None));
+        assert!(!edf([Task::new(2, 4).with_deadline(3).with_jitter(2)], None));
+    }
+
+    #[test]
+    fn edf_srp_blocking() {
+        // the task with the longer deadline blocks the other one within its deadline
+        let ts = [Task::new(1, 4), Task::new(2, 6)];
+
+        assert!(edf(ts, Some(&[0, 3])));
+        assert!(!edf(ts, Some(&[0, 4])));
+        assert!(edf(ts, Some(&[4, 0])));
+    }
+
+    fn task(cost: Time, period: Time, priority: u64) -> Task {
+        Task { priority, ..Task::new(cost, period) }
+    }
+
+    #[test]
+    fn fp_buttazzo() {
+        // Buttazzo's example, in "Hard real-time computing systems" (Springer, 2011)
+        let ts = [task(1, 4, 0), task(2, 6, 1), task(3, 10, 2)];
+
+        assert_eq!(*fp(ts, None), [Some(1), Some(3), Some(10)]);
+        assert_eq!(*fp(ts, Some(&[2, 0, 0])), [Some(3), Some(3), Some(10)]);
+        assert_eq!(*fp([task(1, 4, 0), task(2, 6, 1), task(4, 10, 2)], None), [Some(1), Some(3), None]);
+    }
+
+    #[test]
+    fn fp_arbitrary_deadlines() {
+        // Tindell, Burns and Wellings' example, where the first job of the second task
+        // is not the one with the worst response time
+        let ts = [task(26, 70, 0).with_deadline(200), task(62, 100, 1).with_deadline(200)];
+
+        assert_eq!(*fp(ts, None), [Some(26), Some(118)]);
+    }
+
+    #[test]
+    fn fp_jitter() {
+        let ts = [task(1, 4, 0), task(2, 6, 1)];
+
+        assert_eq!(*fp(ts, None), [Some(1), Some(3)]);
+        assert_eq!(*fp([ts[0].with_jitter(2), ts[1]], None), [Some(3), Some(4)]);
+    }
+
+    #[test]
+    fn fp_ties() {
+        // tasks with equal priority interfere with each other
+        assert_eq!(*fp([task(1, 4, 0), task(2, 6, 0)], None), [Some(3), Some(3)]);
+    }
+
+    #[test]
+    fn fp_thresholds() {
+        // the third task induces blocking on the second one through its threshold
+        let ts = [task(1, 4, 0), task(2, 6, 1), task(2, 12, 2).with_threshold(0)];
+
+        assert_eq!(*fp(ts, None), [Some(1), Some(6), Some(6)]);
+        assert_eq!(*fp(ts, Some(&[2, 0, 0])), [Some(3), Some(6), Some(6)]);
+    }
+}
@@ -0,0 +1,310 @@
+//! The DAG (parallel) task model.
+//!
+//! Each job of a DAG task consists of a set of nodes, each with its own WCET, that
+//! may execute in parallel on different CPUs as long as the precedence constraints
+//! given by the edges of the DAG are respected. A DAG task is characterized by its
+//! _volume_, i.e. the total WCET of its nodes, and its _length_, i.e. the total WCET
+//! along its longest path.
+
+use crate::{
+    task::{Task, AsTask, Time},
+    partition::ClusterTest,
+    bound::gfp
+};
+
+use dashu::rational::RBig;
+
+/// A DAG task.
+#[derive(Clone)]
+pub struct DagTask {
+    /// The task's period.
+    pub period: Time,
+    /// The task's relative deadline.
+    pub deadline: Time,
+    /// The task's priority, if defined.
+    pub priority: u64,
+    costs: Box<[Time]>,
+    edges: Box<[(usize, usize)]>,
+    order: Box<[usize]>
+}
+
+impl DagTask {
+    /// Constructs a new `DagTask` with nodes with WCETs `costs`, edges `edges` from the
+    /// index of a node to that of its successor, period `period`, implicit deadline and
+    /// maximum priority.
+    ///
+    /// # Panics
+    ///
+    /// Panics if an edge refers to a non-existent node or if the edges form a cycle.
+    pub fn new(
+        costs: impl IntoIterator<Item = Time>,
+        edges: impl IntoIterator<Item = (usize, usize)>,
+        period: Time
+    ) -> Self {
+        let costs = costs.into_iter().collect::<Box<_>>();
+        let edges = edges.into_iter().collect::<Box<_>>();
+
+        // sort nodes topologically using Kahn's algorithm
+        let mut preds = vec![0; costs.len()];
+
+        for &(from, to) in &edges {
+            assert!(from < costs.len() && to < costs.len());
+            preds[to] += 1;
+        }
+
+        let mut order = (0 .. costs.len()).filter(|n| preds[*n] == 0).collect::<Vec<_>>();
+        let mut next = 0;
+
+        while let Some(&node) = order.get(next) {
+            for &(_, to) in edges.iter().filter(|(from, _)| *from == node) {
+                preds[to] -= 1;
+
+                if preds[to] == 0 {
+                    order.push(to);
+                }
+            }
+
+            next += 1;
+        }
+
+        assert_eq!(order.len(), costs.len(), "edges must not form a cycle");
+
+        Self {
+            period,
+            deadline: period,
+            priority: 0,
+            costs,
+            edges,
+            order: order.into_boxed_slice()
+        }
+    }
+
+    /// Returns the task with new deadline `deadline`.
+    pub fn with_deadline(self, deadline: Time) -> Self {
+        Self { deadline, ..self }
+    }
+
+    /// Returns the task with new priority `priority`.
+    pub fn with_priority(self, priority: u64) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// Returns the WCET of each node of the task.
+    pub fn costs(&self) -> &[Time] {
+        &self.costs
+    }
+
+    /// Returns the edges of the task.
+    pub fn edges(&self) -> &[(usize, usize)] {
+        &self.edges
+    }
+
+    /// Returns the volume of the task, i.e. the total WCET of its nodes.
+    pub fn volume(&self) -> Time {
+        self.costs.iter().sum()
+    }
+
+    /// Returns the length of the task, i.e. the total WCET of the nodes along its
+    /// longest path.
+    pub fn length(&self) -> Time {
+        let mut finish = vec![0; self.costs.len()];
+
+        for &node in &self.order {
+            let start = self.edges.iter()
+                                  .filter(|(_, to)| *to == node)
+                                  .map(|(from, _)| finish[*from])
+                                  .max()
+                                  .unwrap_or_default();
+
+            finish[node] = start + self.costs[node];
+        }
+
+        finish.into_iter().max().unwrap_or_default()
+    }
+
+    /// Returns the utilization of the task, i.e. its volume over its period.
+    pub fn utilization(&self) -> RBig {
+        RBig::from(self.volume()) / RBig::from(self.period)
+    }
+}
+
+/// A `DagTask` is analyzed by tests for sequential tasks as the task obtained by
+/// executing all of its nodes sequentially.
+impl AsTask for DagTask {
+    fn as_task(&self) -> Task {
+        Task {
+            priority: self.priority,
+            ..Task::new(self.volume(), self.period).with_deadline(self.deadline)
+        }
+    }
+}
+
+/// Li et al.'s federated scheduling, as described in "Analysis of federated and global
+/// scheduling for parallel real-time tasks" (ECRTS, 2014), for constrained deadlines.
+///
+/// Each heavy task, i.e. with volume greater than its deadline, is assigned
+/// `ceil((C - L) / (D - L))` dedicated CPUs, where `C` is its volume and `L` its length.
+/// The remaining light tasks are sequentialized and must pass `test` on the remaining
+/// CPUs, e.g. by partitioning them with a [`Partitioner`](`crate::partition::Partitioner`).
+///
+/// Returns the number of dedicated CPUs of each task, which is `0` for light tasks,
+/// if the task-set is schedulable, otherwise `None`.
+pub fn federated(ts: &[DagTask], num_cpus: usize, test: impl ClusterTest<Task>) -> Option<Box<[usize]>> {
+    let mut left = num_cpus;
+    let mut light = Vec::new();
+
+    let cpus = ts.iter().map(|t| {
+        let (vol, len) = (t.volume(), t.length());
+
+        if t.deadline > t.period || len > t.deadline {
+            return None;
+        } else if vol <= t.deadline {
+            light.push(t.as_task());
+            return Some(0);
+        } else if len == t.deadline {
+            return None;
+        }
+
+        let num = usize::try_from((vol - len).div_ceil(t.deadline - len)).ok()?;
+        left = left.checked_sub(num)?;
+
+        Some(num)
+    }).collect::<Option<Box<_>>>()?;
+
+    (light.is_empty() || test.accepts(&light, left)).then_some(cpus)
+}
+
+/// Li et al.'s capacity augmentation bound for global EDF, as described in "Analysis
+/// of global EDF for parallel tasks" (ECRTS, 2013).
+///
+/// The task-set is deemed schedulable if its total utilization is at most `num_cpus / b`
+/// and the length of each task is at most its deadline over `b`, with `b = (3 + √5) / 2`,
+/// here rounded up to `2.618034`.
+///
+/// Returns the result of the test if `ts` is implicit, otherwise `None`.
+pub fn gedf(ts: &[DagTask], num_cpus: usize) -> Option<bool> {
+    if ts.iter().any(|t| t.deadline != t.period) {
+        return None;
+    }
+
+    let bound = RBig::from(2_618_034) / RBig::from(1_000_000);
+    let util = ts.iter().fold(RBig::ZERO, |acc, t| acc + t.utilization());
+
+    Some(&util * &bound <= RBig::from(num_cpus) && ts.iter().all(|t| {
+        RBig::from(t.length()) * &bound <= RBig::from(t.deadline)
+    }))
+}
+
+/// Returns an upper bound on the workload of a DAG task with volume `vol`, period `period`
+/// and response time `resp` within a window of length `len`, if its workload is spread
+/// evenly over `num_cpus` CPUs.
+fn workload(vol: Time, period: Time, resp: Time, len: Time, num_cpus: Time) -> Time {
+    // time quantities are scaled by the number of CPUs to keep them integral
+    let span = (num_cpus * (len + resp)).saturating_sub(vol);
+    let scaled = num_cpus * period;
+
+    span / scaled * vol + vol.min(span % scaled)
+}
+
+/// Melani et al.'s response-time analysis for global fixed-priority scheduling of DAG
+/// tasks, as described in "Response-time analysis of conditional DAG tasks in
+/// multiprocessor systems" (ECRTS, 2015), without conditional nodes.
+///
+/// Priorities are given by each task's priority, with lower values meaning higher
+/// priority and ties interfering with each other. Results are as for [`gfp::rta`]:
+/// returns `None` if `ts` is not constrained, and otherwise a response-time bound for
+/// each task, or `None` for tasks that cannot be shown to meet their deadline.
+pub fn gfp(ts: &[DagTask], num_cpus: usize) -> Option<Box<[Option<Time>]>> {
+    if ts.iter().any(|t| t.deadline > t.period) {
+        return None;
+    } else if num_cpus == 0 {
+        return Some(vec![None; ts.len()].into());
+    }
+
+    let m = num_cpus as Time;
+    let seq = ts.iter().map(AsTask::as_task).collect::<Box<_>>();
+    let lens = ts.iter().map(DagTask::length).collect::<Box<_>>();
+
+    Some(gfp::analyze(&seq, |k, hp, resp| {
+        let (vol, len) = (seq[k].cost, lens[k]);
+        let mut r = len;
+
+        loop {
+            let inter = hp.iter()
+                          .map(|i| workload(seq[*i].cost, seq[*i].period, resp[*i], r, m))
+                          .sum::<Time>();
+
+            // work outside the longest path is spread evenly over all CPUs
+            let next = len + (vol - len + inter) / m;
+
+            if next > seq[k].deadline {
+                return None;
+            } else if next == r {
+                return Some(r);
+            }
+
+            r = next;
+        }
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bound::gedf as seq_gedf;
+
+    /// A fork-join DAG with volume `7` and length `5`.
+    fn diamond(period: Time) -> DagTask {
+        DagTask::new([1, 2, 3, 1], [(0, 1), (0, 2), (1, 3), (2, 3)], period)
+    }
+
+    #[test]
+    fn volume_length() {
+        let t = diamond(10);
+
+        assert_eq!((t.volume(), t.length()), (7, 5));
+        assert_eq!(t.as_task().cost, 7);
+        assert_eq!(DagTask::new([2, 2, 2], [], 10).length(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    fn cycle() {
+        DagTask::new([1, 1], [(0, 1), (1, 0)], 10);
+    }
+
+    #[test]
+    fn federated_cpus() {
+        let test = |ts: &[Task], m| seq_gedf::gfb(ts.iter(), m);
+
+        // the heavy task needs ceil((20 - 5) / (10 - 5)) = 3 dedicated CPUs
+        let heavy = DagTask::new([1, 4, 4, 4, 4, 3], (1 .. 6).map(|n| (0, n)), 10);
+        let ts = [heavy, DagTask::new([2], [], 10)];
+
+        assert_eq!(federated(&ts, 4, test).as_deref(), Some([3, 0].as_slice()));
+        assert_eq!(federated(&ts, 3, test), None);
+        assert_eq!(federated(&ts[.. 1], 3, test).as_deref(), Some([3].as_slice()));
+    }
+
+    #[test]
+    fn capacity_augmentation() {
+        assert_eq!(gedf(&[diamond(14)], 2), Some(true));
+        assert_eq!(gedf(&[diamond(13)], 2), Some(false));
+        assert_eq!(gedf(&[diamond(14).with_deadline(13)], 2), None);
+    }
+
+    #[test]
+    fn fixed_priority() {
+        // sequential tasks on one CPU, as in Buttazzo's example
+        let ts = [DagTask::new([1], [], 4),
+                  DagTask::new([2], [], 6).with_priority(1),
+                  DagTask::new([3], [], 12).with_priority(2)];
+        assert_eq!(gfp(&ts, 1).as_deref(), Some([Some(1), Some(3), Some(10)].as_slice()));
+
+        let ts = [DagTask::new([1], [], 4), diamond(8).with_priority(1)];
+        assert_eq!(gfp(&ts, 2).as_deref(), Some([Some(1), Some(7)].as_slice()));
+        assert_eq!(gfp(&ts, 1).as_deref(), Some([Some(1), None].as_slice()));
+        assert_eq!(gfp(&ts, 0).as_deref(), Some([None, None].as_slice()));
+    }
+}
@@ -0,0 +1,331 @@
+//! The gang and moldable (parallel) task models.
+//!
+//! Each job of a gang task executes on a fixed number of CPUs simultaneously, i.e. all of
+//! its threads start, are preempted and resume together, as described by Kato and
+//! Ishikawa in "Gang EDF scheduling of parallel task systems" (RTSS, 2009). A moldable
+//! task may instead execute on any number of CPUs up to some bound, with a cost that
+//! depends on it as given by its speedup profile; selecting a number of CPUs for each
+//! moldable task offline turns it into a gang task.
+//!
+//! Under gang scheduling, pending jobs are considered in order of priority, and each is
+//! scheduled if enough CPUs are left for it, so that lower-priority jobs may use CPUs
+//! that a higher-priority job cannot. Hence, whenever a pending job of a gang task on
+//! `k` CPUs does not execute, jobs of higher or equal priority occupy more than `m - k`
+//! of the `m` CPUs.
+
+use crate::{
+    task::{Task, Set, Time},
+    bound::{gedf::{carry_in, edf_workload}, gfp}
+};
+
+use dashu::rational::RBig;
+
+/// A gang task.
+#[derive(Clone, Copy)]
+pub struct GangTask {
+    /// The task, whose cost is that of each of its threads.
+    pub task: Task,
+    /// The number of CPUs that each job of the task executes on simultaneously.
+    pub cpus: usize
+}
+
+impl GangTask {
+    /// Constructs a new `GangTask` executing `task` on `cpus` CPUs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cpus` is zero.
+    pub fn new(task: Task, cpus: usize) -> Self {
+        assert!(cpus > 0);
+
+        Self { task, cpus }
+    }
+
+    /// Returns the area of the task, i.e. its cost times its number of CPUs.
+    pub fn area(&self) -> Time {
+        self.task.cost * self.cpus as Time
+    }
+
+    /// Returns the utilization of the task, i.e. its area over its period.
+    pub fn utilization(&self) -> RBig {
+        RBig::from(self.area()) / RBig::from(self.task.period)
+    }
+}
+
+/// Trait for objects that can be analyzed as a [`GangTask`].
+pub trait AsGang {
+    /// Returns the `GangTask` that this object is analyzed as.
+    fn as_gang(&self) -> GangTask;
+}
+
+impl AsGang for GangTask {
+    fn as_gang(&self) -> GangTask {
+        *self
+    }
+}
+
+/// A `Task` is a gang task on a single CPU.
+impl AsGang for Task {
+    fn as_gang(&self) -> GangTask {
+        GangTask::new(*self, 1)
+    }
+}
+
+impl<T: AsGang + ?Sized> AsGang for &T {
+    fn as_gang(&self) -> GangTask {
+        (**self).as_gang()
+    }
+}
+
+/// Collects the gang tasks that the items of `ts` are analyzed as.
+fn collect(ts: impl IntoIterator<Item: AsGang>) -> Box<[GangTask]> {
+    ts.into_iter()
+      .map(|t| t.as_gang())
+      .collect()
+}
+
+/// A moldable task.
+#[derive(Clone)]
+pub struct MoldableTask {
+    /// The task, whose cost is superseded by the speedup profile.
+    pub task: Task,
+    costs: Box<[Time]>
+}
+
+impl MoldableTask {
+    /// Constructs a new `MoldableTask` with the parameters of `task` and speedup profile
+    /// `costs`, such that its cost on `k` CPUs is `costs[k - 1]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `costs` is empty.
+    pub fn new(task: Task, costs: impl IntoIterator<Item = Time>) -> Self {
+        let costs = costs.into_iter().collect::<Box<_>>();
+        assert!(!costs.is_empty());
+
+        Self { task, costs }
+    }
+
+    /// Constructs a new `MoldableTask` on up to `max_cpus` CPUs whose speedup follows
+    /// Amdahl's law: a part `parallel` of the cost of `task` is evenly divided among
+    /// all CPUs, rounding up, and the rest is sequential.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parallel` exceeds the cost of `task` or if `max_cpus` is zero.
+    pub fn amdahl(task: Task, parallel: Time, max_cpus: usize) -> Self {
+        let seq = task.cost - parallel;
+
+        Self::new(task, (1 ..= max_cpus as Time).map(|k| seq + parallel.div_ceil(k)))
+    }
+
+    /// Returns the speedup profile of the task, i.e. its cost on `1`, `2`, ... CPUs.
+    pub fn costs(&self) -> &[Time] {
+        &self.costs
+    }
+
+    /// Returns the largest number of CPUs that the task can execute on.
+    pub fn max_cpus(&self) -> usize {
+        self.costs.len()
+    }
+
+    /// Returns the gang task that the task executes as on `cpus` CPUs.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cpus` is zero or exceeds [`max_cpus`](`MoldableTask::max_cpus`).
+    pub fn mode(&self, cpus: usize) -> GangTask {
+        GangTask::new(Task { cost: self.costs[cpus - 1], ..self.task }, cpus)
+    }
+}
+
+/// Kato and Ishikawa's test for gang EDF scheduling, as described in "Gang EDF scheduling
+/// of parallel task systems" (RTSS, 2009): the workload bound of Bertogna, Cirinei and
+/// Lipari's test ([`gedf::bcl`](`crate::bound::gedf::bcl`)) is weighted by the number of
+/// CPUs of each task, and a task on `k` CPUs only requires `m - k + 1` of them to be
+/// busy for it not to execute.
+///
+/// Since time is discrete, a job misses its deadline only if it does not execute for
+/// `D - C + 1` time units, which bounds the interference of each task; with every task
+/// on a single CPU, the test is hence never more pessimistic than
+/// [`gedf::bcl`](`crate::bound::gedf::bcl`).
+///
+/// Returns the result of the test if `ts` is constrained, otherwise `None`.
+pub fn edf(ts: impl IntoIterator<Item: AsGang>, num_cpus: usize) -> Option<bool> {
+    let ts = collect(ts);
+
+    if !ts.iter().map(|t| &t.task).constrained() {
+        return None;
+    }
+
+    Some(ts.iter().enumerate().all(|(k, gk)| {
+        let tk = &gk.task;
+
+        if gk.cpus > num_cpus {
+            return false;
+        }
+
+        let Some(slack) = tk.deadline.checked_sub(tk.jitter + tk.cost) else {
+            return false;
+        };
+
+        let len = tk.deadline - tk.jitter;
+        let sum = ts.iter()
+                    .enumerate()
+                    .filter(|(i, _)| *i != k)
+                    .map(|(_, gi)| gi.cpus as Time * edf_workload(&gi.task, len, 0).min(slack + 1))
+                    .sum::<Time>();
+
+        sum < (num_cpus - gk.cpus + 1) as Time * (slack + 1)
+    }))
+}
+
+/// Response-time analysis for gang fixed-priority scheduling, extending Bertogna and
+/// Cirinei's analysis ([`gfp::rta`]) as [`edf`] extends BCL: the interference of each
+/// higher-priority task is weighted by its number of CPUs, and divided among the
+/// `m - k + 1` CPUs that must be busy for a task on `k` CPUs not to execute.
+///
+/// Priorities are given by the priority of each task, with lower values meaning higher
+/// priority and ties interfering with each other. Results are as for [`gfp::rta`]:
+/// returns `None` if `ts` is not constrained, and otherwise a response-time bound for
+/// each task, or `None` for tasks that cannot be shown to meet their deadline, including
+/// those that need more than `num_cpus` CPUs.
+pub fn fp(ts: impl IntoIterator<Item: AsGang>, num_cpus: usize) -> Option<Box<[Option<Time>]>> {
+    let ts = collect(ts);
+    let seq = ts.iter().map(|t| t.task).collect::<Box<_>>();
+
+    if !seq.iter().constrained() {
+        return None;
+    }
+
+    Some(gfp::analyze(&seq, |k, hp, resp| {
+        let (tk, cpus) = (&seq[k], ts[k].cpus);
+        let free = (num_cpus + 1).checked_sub(cpus).filter(|f| *f > 0)? as Time;
+
+        gfp::fixed_point(tk, |r| {
+            let inter = hp.iter()
+                          .map(|i| {
+                              ts[*i].cpus as Time * carry_in(&seq[*i], resp[*i], r).min(r - tk.cost + 1)
+                          })
+                          .sum::<Time>();
+
+            tk.cost + inter / free
+        })
+    }))
+}
+
+/// Selects the number of CPUs of each moldable task of `ts` so that the resulting gang
+/// tasks pass `test`, e.g. [`edf`] or [`fp`] on `num_cpus` CPUs.
+///
+/// Each task starts from the fewest CPUs on which its cost fits within its deadline,
+/// shortened by its jitter. While `test` fails, the task with the highest density is
+/// moved to the next number of CPUs, up to `num_cpus`, that strictly lowers its cost;
+/// this greedily trades area for a shorter execution where it is most needed.
+///
+/// Returns the number of CPUs of each task, or `None` if no selection was found.
+pub fn select(
+    ts: &[MoldableTask],
+    num_cpus: usize,
+    test: impl Fn(&[GangTask]) -> bool
+) -> Option<Box<[usize]>> {
+    let limit = |t: &MoldableTask| t.max_cpus().min(num_cpus);
+    let window = |t: &MoldableTask| t.task.deadline.saturating_sub(t.task.jitter);
+
+    let mut cpus = ts.iter().map(|t| {
+        (1 ..= limit(t)).find(|k| t.costs[k - 1] <= window(t))
+    }).collect::<Option<Box<_>>>()?;
+
+    loop {
+        let gangs = ts.iter().zip(&cpus).map(|(t, k)| t.mode(*k)).collect::<Box<_>>();
+
+        if test(&gangs) {
+            return Some(cpus);
+        }
+
+        // the next mode of each task, if any, with its current density
+        let next = ts.iter().zip(&cpus).enumerate().filter_map(|(i, (t, k))| {
+            let cost = t.costs[k - 1];
+            let up = (k + 1 ..= limit(t)).find(|j| t.costs[j - 1] < cost)?;
+
+            Some((RBig::from(cost) / RBig::from(window(t).max(1)), i, up))
+        });
+
+        let (_, i, up) = next.max_by(|a, b| a.0.cmp(&b.0))?;
+        cpus[i] = up;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bound::gedf;
+
+    fn task(cost: Time, period: Time, priority: u64) -> Task {
+        Task { priority, ..Task::new(cost, period) }
+    }
+
+    #[test]
+    fn moldable() {
+        // 8 of 10 time units can be divided among up to 4 CPUs
+        let t = MoldableTask::amdahl(Task::new(10, 10), 8, 4);
+
+        assert_eq!(t.costs(), [10, 6, 5, 4]);
+        assert_eq!(t.max_cpus(), 4);
+        assert_eq!((t.mode(3).task.cost, t.mode(3).cpus, t.mode(3).area()), (5, 3, 15));
+        assert_eq!(t.mode(2).utilization(), RBig::from(6) / RBig::from(5));
+    }
+
+    #[test]
+    fn sequential() {
+        let dhall = [Task::new(2, 20), Task::new(2, 20), Task::new(20, 21)];
+        let light = [Task::new(1, 10); 4];
+
+        for ts in [&dhall[..], &light[..]] {
+            assert!(edf(ts, 2) >= gedf::bcl(ts, 2));
+        }
+
+        assert_eq!(edf(light, 2), Some(true));
+        assert_eq!(edf([Task::new(1, 4).with_deadline(5)], 2), None);
+    }
+
+    #[test]
+    fn gangs() {
+        // two gangs on both CPUs, each of which must wait for the other
+        let ts = [GangTask::new(task(2, 10, 0), 2), GangTask::new(task(3, 10, 1), 2)];
+
+        assert_eq!(edf(ts, 2), Some(true));
+        assert_eq!(fp(ts, 2).as_deref(), Some([Some(2), Some(7)].as_slice()));
+
+        // neither gang fits on a single CPU
+        assert_eq!(edf(ts, 1), Some(false));
+        assert_eq!(fp(ts, 1).as_deref(), Some([None, None].as_slice()));
+
+        // with a third CPU, the sequential task can run alongside the gang
+        let ts = [GangTask::new(task(2, 10, 0), 2), GangTask::new(task(3, 10, 1), 1)];
+        assert_eq!(fp(ts, 3).as_deref(), Some([Some(2), Some(3)].as_slice()));
+    }
+
+    #[test]
+    fn selection() {
+        let ts = [MoldableTask::amdahl(Task::new(10, 10), 8, 4),
+                  MoldableTask::new(Task::new(6, 10), [6, 3])];
+
+        // each task must complete within half its deadline: the denser task is given
+        // more CPUs first, ties going to the later task
+        let half = |gs: &[GangTask]| gs.iter().all(|g| 2 * g.task.cost <= g.task.deadline);
+
+        assert_eq!(select(&ts, 4, half).as_deref(), Some([3, 2].as_slice()));
+        assert_eq!(select(&ts, 2, half), None);
+        assert_eq!(select(&ts, 4, |_| true).as_deref(), Some([1, 1].as_slice()));
+
+        // the first task needs at least 2 CPUs to fit within a deadline of 6
+        let ts = [MoldableTask::amdahl(Task::new(10, 10).with_deadline(6), 8, 4),
+                  MoldableTask::new(task(4, 8, 1), [4, 3])];
+        let test = |m| move |gs: &[GangTask]| fp(gs, m).is_some_and(|r| r.iter().all(Option::is_some));
+
+        assert_eq!(select(&ts, 3, test(3)).as_deref(), Some([2, 1].as_slice()));
+        assert_eq!(select(&ts, 2, test(2)), None);
+        assert_eq!(select(&ts, 1, test(1)), None);
+    }
+}
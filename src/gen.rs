@@ -2,7 +2,8 @@
 
 use crate::{
     task::{Task, Time},
-    rsrc::{System, RequestKind, Mutex, Rw, Request}
+    rsrc::{System, RequestKind, Mutex, Rw, Request},
+    dag::DagTask
 };
 
 use rand::{
@@ -114,7 +115,8 @@ impl<R1, R2> Tasks<R1, R2> {
     /// The task-set to be generated will have normalized utilization
     /// `norm_util` for `num_cpus` CPUs. The number of tasks will be chosen
     /// uniformly at random from `num_tasks`, and their period also uniformly
-    /// at random from `period`. Each task will have implicit deadline.
+    /// at random from `period`. Each task will have implicit deadline and maximum
+    /// priority; use [`prio`](`crate::prio`) to assign fixed priorities.
     ///
     /// The utilizations of each task are picked uniformly at random from the
     /// space described above; the algorithm used to do so is
@@ -148,11 +150,69 @@ impl<R1, R2> Tasks<R1, R2> where R1: SampleRange<usize>,
             Task::new(
                 (period as f64 * u).ceil() as Time,
                 period
-            ).edf()
+            )
         }).collect()
     }
 }
 
+/// Generator for DAG tasks.
+pub struct Dag<R1, R2> {
+    util: f64,
+    num: R1,
+    prob_edge: f64,
+    cost: R2
+}
+
+impl<R1, R2> Dag<R1, R2> {
+    /// Constructs a new `Dag` with the given parameters.
+    ///
+    /// The task to be generated will have utilization `util`, rounded down by
+    /// rounding up its period. The number of nodes will be chosen uniformly at
+    /// random from `num_nodes`, and the WCET of each node also uniformly at random
+    /// from `cost`. The task will have implicit deadline.
+    ///
+    /// Edges are generated according to the Erdős–Rényi model: for each pair of
+    /// nodes, an edge from the node with the lower index to the other is added with
+    /// probability `prob_edge`, which guarantees that the graph is acyclic.
+    pub fn new(util: f64, num_nodes: R1, prob_edge: f64, cost: R2) -> Self {
+        assert!(util > 0.0);
+        assert!((0.0 ..= 1.0).contains(&prob_edge));
+
+        Self {
+            util,
+            num: num_nodes,
+            prob_edge,
+            cost
+        }
+    }
+}
+
+impl<R1, R2> Dag<R1, R2> where R1: SampleRange<usize>,
+                               R2: SampleRange<Time> + Clone {
+    /// Runs the generator.
+    ///
+    /// Returns a DAG task as described in [`Dag::new`].
+    #[allow(clippy::cast_precision_loss, clippy::cast_sign_loss,
+            clippy::cast_possible_truncation)]
+    pub fn gen(self) -> DagTask {
+        let Self { util, num, prob_edge, cost } = self;
+        let mut rng = rand::thread_rng();
+
+        let num = rng.gen_range(num);
+        let costs = (0 .. num).map(|_| rng.gen_range(cost.clone()))
+                              .collect::<Vec<_>>();
+
+        let edges = (0 .. num).flat_map(|i| (i + 1 .. num).map(move |j| (i, j)))
+                              .filter(|_| rng.gen_bool(prob_edge))
+                              .collect::<Vec<_>>();
+
+        let volume = costs.iter().sum::<Time>();
+        let period = ((volume as f64 / util).ceil() as Time).max(1);
+
+        DagTask::new(costs, edges, period)
+    }
+}
+
 /// Marks request kinds that can be generated by [`Requests`].
 pub trait RequestGen: RequestKind {
     /// Type that parameterizes the final generation of each request.
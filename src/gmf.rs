@@ -0,0 +1,438 @@
+//! The generalized multiframe (GMF) task model.
+//!
+//! A GMF task cycles through a fixed sequence of _frames_, each with its own cost,
+//! relative deadline and minimum separation from the arrival of the next frame, as
+//! described by Baruah et al. in "Generalized multiframe tasks" (Real-Time Systems,
+//! 1999). Multiframe tasks, as described by Mok and Chen in "A multiframe model for
+//! real-time tasks" (IEEE TSE, 1997), are GMF tasks whose frames only differ by cost.
+//!
+//! The tests in this module are uniprocessor tests over the demand-bound and
+//! request-bound functions of GMF tasks. They accept any iterator of [`AsGmf`] items,
+//! so that GMF tasks can be analyzed together with sporadic [`Task`]s.
+
+use crate::task::{self, Task, AsTask, Time};
+
+use dashu::rational::RBig;
+
+/// A single frame of a GMF task.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Frame {
+    /// The frame's cost.
+    pub cost: Time,
+    /// The frame's relative deadline, measured from its arrival.
+    pub deadline: Time,
+    /// The minimum time between the arrival of the frame and that of the next one.
+    pub separation: Time
+}
+
+impl Frame {
+    /// Constructs a new `Frame` with the given `cost` and `separation`, and implicit
+    /// deadline (equal to `separation`).
+    pub fn new(cost: Time, separation: Time) -> Self {
+        Self { cost, deadline: separation, separation }
+    }
+
+    /// Returns the frame with new deadline `deadline`.
+    pub fn with_deadline(self, deadline: Time) -> Self {
+        Self { deadline, ..self }
+    }
+}
+
+/// A GMF task.
+#[derive(Clone)]
+pub struct GmfTask {
+    frames: Box<[Frame]>,
+    /// The task's fixed priority, if defined; lower values mean higher priority.
+    pub priority: u64,
+    /// The task's release jitter, common to all frames.
+    pub jitter: Time
+}
+
+impl GmfTask {
+    /// Constructs a new `GmfTask` with frames `frames`, maximum priority and no jitter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `frames` is empty or contains a frame with zero separation.
+    pub fn new(frames: impl IntoIterator<Item = Frame>) -> Self {
+        let frames = frames.into_iter().collect::<Box<_>>();
+
+        assert!(!frames.is_empty());
+        assert!(frames.iter().all(|f| f.separation > 0));
+
+        Self { frames, priority: 0, jitter: 0 }
+    }
+
+    /// Constructs a new multiframe `GmfTask` with one frame for each cost in `costs`,
+    /// all with separation `period` and implicit deadline.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `costs` is empty or `period` is zero.
+    pub fn multiframe(costs: impl IntoIterator<Item = Time>, period: Time) -> Self {
+        Self::new(costs.into_iter().map(|c| Frame::new(c, period)))
+    }
+
+    /// Returns the task with new priority `priority`.
+    pub fn with_priority(self, priority: u64) -> Self {
+        Self { priority, ..self }
+    }
+
+    /// Returns the task with new release jitter `jitter`.
+    pub fn with_jitter(self, jitter: Time) -> Self {
+        Self { jitter, ..self }
+    }
+
+    /// Returns the frames of the task.
+    pub fn frames(&self) -> &[Frame] {
+        &self.frames
+    }
+
+    /// Returns the length of a cycle of the task, i.e. the total separation of its frames.
+    pub fn cycle(&self) -> Time {
+        self.frames.iter().map(|f| f.separation).sum()
+    }
+
+    /// Returns the volume of the task, i.e. the total cost of its frames.
+    pub fn volume(&self) -> Time {
+        self.frames.iter().map(|f| f.cost).sum()
+    }
+
+    /// Returns the utilization of the task, i.e. its volume over its cycle.
+    pub fn utilization(&self) -> RBig {
+        RBig::from(self.volume()) / RBig::from(self.cycle())
+    }
+
+    /// Returns the deadline of frame `j`, shortened by the release jitter of the task.
+    fn window(&self, j: usize) -> Time {
+        self.frames[j].deadline.saturating_sub(self.jitter)
+    }
+
+    /// Returns the largest window deadline of a frame of the task.
+    fn max_window(&self) -> Time {
+        (0 .. self.frames.len()).map(|j| self.window(j)).max().unwrap_or_default()
+    }
+
+    /// Calls `f` with each frame of the task and its arrival time, starting from frame
+    /// `start` at time `0`, until `f` returns `false`.
+    fn walk(&self, start: usize, mut f: impl FnMut(&Frame, usize, Time) -> bool) {
+        let mut arrival = 0;
+
+        for j in (0 .. self.frames.len()).cycle().skip(start) {
+            if !f(&self.frames[j], j, arrival) {
+                break;
+            }
+
+            arrival += self.frames[j].separation;
+        }
+    }
+
+    /// Returns the demand-bound function of the task for an interval of length `t`, i.e.
+    /// the largest total cost of a sequence of frames with both release and deadline
+    /// within the interval.
+    ///
+    /// Release jitter is accounted for by shortening the deadline of each frame, as in
+    /// [`uni::edf`](`crate::bound::uni::edf`).
+    pub fn dbf(&self, t: Time) -> Time {
+        let (cycle, volume, max_dl) = (self.cycle(), self.volume(), self.max_window());
+
+        // past a full cycle and the largest deadline, demand grows by a volume per cycle
+        let full = t.saturating_sub(max_dl) / cycle;
+        let t = t - full * cycle;
+
+        (0 .. self.frames.len()).map(|start| {
+            let mut demand = 0;
+
+            self.walk(start, |frame, j, arrival| {
+                if arrival + self.window(j) <= t {
+                    demand += frame.cost;
+                }
+
+                arrival <= t
+            });
+
+            demand
+        }).max().unwrap_or_default() + full * volume
+    }
+
+    /// Returns the request-bound function of the task for an interval of length `t`, i.e.
+    /// the largest total cost of a sequence of frames released within the interval,
+    /// accounting for release jitter as [`Task::max_jobs`].
+    pub fn rbf(&self, t: Time) -> Time {
+        let (cycle, volume) = (self.cycle(), self.volume());
+        let t = t + self.jitter;
+
+        // every frame of a cycle is released within an interval of its length
+        let full = t / cycle;
+        let t = t % cycle;
+
+        (0 .. self.frames.len()).map(|start| {
+            let mut request = 0;
+
+            self.walk(start, |frame, _, arrival| {
+                if arrival < t {
+                    request += frame.cost;
+                }
+
+                arrival < t
+            });
+
+            request
+        }).max().unwrap_or_default() + full * volume
+    }
+
+    /// Returns every interval length up to `limit` at which the demand-bound function of
+    /// the task may increase.
+    fn steps(&self, limit: Time) -> Vec<Time> {
+        let cycle = self.cycle();
+        let base = cycle + self.max_window();
+        let mut out = Vec::new();
+
+        for start in 0 .. self.frames.len() {
+            self.walk(start, |_, j, arrival| {
+                let mut dl = arrival + self.window(j);
+
+                while dl <= limit {
+                    out.push(dl);
+                    dl += cycle;
+                }
+
+                arrival < base
+            });
+        }
+
+        out
+    }
+}
+
+/// A `GmfTask` is analyzed by tests for sporadic tasks as the task with the largest
+/// cost, the shortest separation and the shortest deadline of its frames, which
+/// over-approximates both its demand and its requests.
+impl AsTask for GmfTask {
+    fn as_task(&self) -> Task {
+        let min = |f: fn(&Frame) -> Time| self.frames.iter().map(f).min().unwrap_or_default();
+        let cost = self.frames.iter().map(|f| f.cost).max().unwrap_or_default();
+
+        Task {
+            priority: self.priority,
+            jitter: self.jitter,
+            ..Task::new(cost, min(|f| f.separation)).with_deadline(min(|f| f.deadline))
+        }
+    }
+}
+
+/// Trait for objects that can be analyzed as a single [`GmfTask`].
+pub trait AsGmf {
+    /// Returns the `GmfTask` that this object is analyzed as.
+    fn as_gmf(&self) -> GmfTask;
+}
+
+impl AsGmf for GmfTask {
+    fn as_gmf(&self) -> GmfTask {
+        self.clone()
+    }
+}
+
+/// A `Task` is a GMF task with a single frame.
+impl AsGmf for Task {
+    fn as_gmf(&self) -> GmfTask {
+        GmfTask {
+            frames: Box::new([Frame::new(self.cost, self.period).with_deadline(self.deadline)]),
+            priority: self.priority,
+            jitter: self.jitter
+        }
+    }
+}
+
+impl<T: AsGmf + ?Sized> AsGmf for &T {
+    fn as_gmf(&self) -> GmfTask {
+        (**self).as_gmf()
+    }
+}
+
+/// Collects the GMF tasks that the items of `ts` are analyzed as.
+fn collect(ts: impl IntoIterator<Item: AsGmf>) -> Box<[GmfTask]> {
+    ts.into_iter()
+      .map(|t| t.as_gmf())
+      .collect()
+}
+
+/// Exact processor-demand test for uniprocessor EDF of GMF tasks, after Baruah et al.'s
+/// "Generalized multiframe tasks" (Real-Time Systems, 1999): the total demand-bound
+/// function must not exceed the length of any interval.
+///
+/// If `blocking` is given, it must contain the longest time for which each task can
+/// block tasks with shorter relative deadlines, as for
+/// [`uni::edf`](`crate::bound::uni::edf`); a task is assumed to block any interval
+/// shorter than its largest frame deadline.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn edf(ts: impl IntoIterator<Item: AsGmf>, blocking: Option<&[Time]>) -> bool {
+    let ts = collect(ts);
+
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), ts.len());
+    }
+
+    if ts.iter().any(|t| t.frames.iter().any(|f| f.cost + t.jitter > f.deadline)) {
+        return false;
+    }
+
+    let util = ts.iter().fold(RBig::ZERO, |acc, t| acc + t.utilization());
+
+    let max_dl = ts.iter()
+                   .flat_map(|t| t.frames.iter().map(|f| f.deadline))
+                   .max()
+                   .unwrap_or_default();
+
+    // each demand-bound function is at most its utilization times the interval, plus
+    // its volume and the demand that its jitter anticipates
+    let limit = if util > RBig::ONE {
+        return false;
+    } else if util < RBig::ONE {
+        let burst = ts.iter().fold(RBig::ZERO, |acc, t| {
+            acc + RBig::from(t.volume()) + RBig::from(t.jitter) * t.utilization()
+        });
+
+        Time::try_from((burst / (RBig::ONE - util)).ceil()).unwrap_or(Time::MAX)
+    } else {
+        let tail = ts.iter().map(|t| t.cycle() + t.max_window()).max().unwrap_or_default();
+
+        match task::hyperperiod(ts.iter().map(GmfTask::cycle)).and_then(|h| h.checked_add(tail)) {
+            Some(l) => l,
+            None => return false
+        }
+    }.max(max_dl);
+
+    let mut points = ts.iter().flat_map(|t| t.steps(limit)).collect::<Vec<_>>();
+    points.sort_unstable();
+    points.dedup();
+
+    points.into_iter().all(|t| {
+        let block = blocking.map_or(0, |b| {
+            ts.iter()
+              .zip(b)
+              .filter(|(task, _)| task.frames.iter().any(|f| f.deadline > t))
+              .map(|(_, b)| *b)
+              .max()
+              .unwrap_or_default()
+        });
+
+        ts.iter().map(|task| task.dbf(t)).sum::<Time>() + block <= t
+    })
+}
+
+/// Response-time analysis for uniprocessor fixed-priority scheduling of GMF tasks,
+/// after Zuhily and Burns' "Exact scheduling analysis of non-accumulatively monotonic
+/// multiframe tasks" (Real-Time Systems, 2009), with the interference of each
+/// higher-priority task bounded by its request-bound function.
+///
+/// Priorities and `blocking` are as for [`uni::fp`](`crate::bound::uni::fp`).
+///
+/// Returns `None` if the deadline of a frame of `ts` exceeds its separation, and
+/// otherwise a bound on the response time of each task over all of its frames,
+/// measured from their arrival, or `None` for tasks that can miss a deadline.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn fp(ts: impl IntoIterator<Item: AsGmf>, blocking: Option<&[Time]>) -> Option<Box<[Option<Time>]>> {
+    let ts = collect(ts);
+
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), ts.len());
+    }
+
+    if ts.iter().any(|t| t.frames.iter().any(|f| f.deadline > f.separation)) {
+        return None;
+    }
+
+    Some((0 .. ts.len()).map(|k| {
+        let tk = &ts[k];
+        let hp = (0 .. ts.len()).filter(|i| *i != k && ts[*i].priority <= tk.priority)
+                                .collect::<Box<_>>();
+
+        tk.frames.iter().map(|frame| {
+            let base = blocking.map_or(0, |b| b[k]) + frame.cost;
+            let mut r = base;
+
+            loop {
+                if r + tk.jitter > frame.deadline {
+                    return None;
+                }
+
+                let next = base + hp.iter().map(|i| ts[*i].rbf(r)).sum::<Time>();
+
+                if next == r {
+                    return Some(r + tk.jitter);
+                }
+
+                r = next;
+            }
+        }).try_fold(0, |acc, r| r.map(|r| r.max(acc)))
+    }).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bound::uni;
+
+    /// Mok and Chen's multiframe task, alternating a long and a short frame.
+    fn multiframe() -> GmfTask {
+        GmfTask::multiframe([3, 1], 4)
+    }
+
+    #[test]
+    fn bound_functions() {
+        let t = multiframe();
+
+        assert_eq!((0 ..= 12).map(|len| t.rbf(len)).collect::<Vec<_>>(),
+                   [0, 3, 3, 3, 3, 4, 4, 4, 4, 7, 7, 7, 7]);
+        assert_eq!((0 ..= 12).map(|len| t.dbf(len)).collect::<Vec<_>>(),
+                   [0, 0, 0, 0, 3, 3, 3, 3, 4, 4, 4, 4, 7]);
+
+        assert_eq!((t.cycle(), t.volume(), t.utilization()), (8, 4, RBig::ONE / RBig::from(2)));
+        assert_eq!(t.clone().with_jitter(1).rbf(4), 4);
+        assert_eq!(t.with_jitter(1).dbf(3), 3);
+    }
+
+    #[test]
+    fn generalized_frames() {
+        // the short-deadline frame either starts or ends the sequence in the interval
+        let t = GmfTask::new([Frame::new(2, 5).with_deadline(3), Frame::new(1, 10)]);
+
+        assert_eq!((t.dbf(2), t.dbf(3), t.dbf(12), t.dbf(13), t.dbf(18)), (0, 2, 2, 3, 5));
+        assert_eq!((t.as_task().cost, t.as_task().period, t.as_task().deadline), (2, 5, 3));
+    }
+
+    #[test]
+    fn sporadic_tasks() {
+        let t = Task::new(2, 5).with_deadline(4);
+
+        let dbf = |len: Time| (len + 1).saturating_sub(4).div_ceil(5) * 2;
+
+        for len in 0 .. 20 {
+            assert_eq!(t.as_gmf().dbf(len), dbf(len));
+            assert_eq!(t.as_gmf().rbf(len), t.max_jobs(len) * 2);
+        }
+    }
+
+    #[test]
+    fn multiframe_vs_sporadic() {
+        let low = Task { priority: 1, ..Task::new(3, 8) };
+        let ts = [multiframe(), low.as_gmf()];
+        let approx = [multiframe().as_task(), low];
+
+        // the sporadic approximation assumes that every frame is long
+        assert_eq!(fp(ts.iter(), None).as_deref(), Some([Some(3), Some(7)].as_slice()));
+        assert_eq!(uni::fp(approx, None).as_ref(), [Some(3), None]);
+
+        assert!(edf(ts.iter(), None));
+        assert!(!uni::edf(approx, None));
+        assert!(!edf(ts.iter(), Some(&[0, 2])));
+    }
+}
@@ -0,0 +1,447 @@
+//! Hierarchical scheduling with the periodic resource model.
+//!
+//! A _component_ is a set of tasks scheduled by a local scheduler on a share of a CPU
+//! provided by the parent scheduler. As in Shin and Lee's "Periodic resource model for
+//! compositional real-time guarantees" (RTSS, 2003), the share is described by a
+//! [`Resource`] `(Π, Θ)`, which guarantees `Θ` time units of execution in every period
+//! of length `Π`. Tests in this module check local schedulability against the
+//! _supply-bound function_ of a resource, and synthesize the resource with the least
+//! budget for a given period, i.e. the _interface_ of the component.
+//!
+//! Interfaces implement [`AsTask`], so that components can be analyzed by the parent
+//! scheduler as ordinary tasks, e.g. by the tests in [`bound::uni`](`crate::bound::uni`).
+
+use crate::{
+    task::{self, Task, AsTask, Time, Policy},
+    rsrc::Request,
+    bound::{lp, uni}
+};
+
+use dashu::{integer::IBig, rational::RBig};
+
+/// A periodic resource, supplying `budget` time units of execution in every `period`.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Resource {
+    /// The resource's period `Π`.
+    pub period: Time,
+    /// The resource's budget `Θ`.
+    pub budget: Time
+}
+
+impl Resource {
+    /// Constructs a new `Resource` with the given `budget` and `period`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `budget` is zero or greater than `period`.
+    pub fn new(budget: Time, period: Time) -> Self {
+        assert!(budget > 0 && budget <= period);
+        Self { period, budget }
+    }
+
+    /// Returns the bandwidth of the resource, i.e. its budget over its period.
+    pub fn bandwidth(&self) -> RBig {
+        RBig::from(self.budget) / RBig::from(self.period)
+    }
+
+    /// Returns the supply-bound function of the resource for an interval of length `t`,
+    /// i.e. the least execution that the resource supplies within any such interval.
+    ///
+    /// In the worst case the budget is supplied as early as possible in a period and
+    /// as late as possible in the following ones, leaving a starvation interval of
+    /// length `2 (Π - Θ)`.
+    pub fn sbf(&self, t: Time) -> Time {
+        let gap = self.period - self.budget;
+
+        if t < gap {
+            return 0;
+        }
+
+        let full = (t - gap) / self.period;
+        full * self.budget + (t - gap - full * self.period).saturating_sub(gap)
+    }
+
+    /// Returns the length of the shortest interval in which the resource is guaranteed
+    /// to supply `work` time units of execution, i.e. the inverse of [`sbf`](`Resource::sbf`).
+    pub fn time_to_supply(&self, work: Time) -> Time {
+        if work == 0 {
+            return 0;
+        }
+
+        let full = (work - 1) / self.budget;
+        2 * (self.period - self.budget) + full * self.period + work - full * self.budget
+    }
+}
+
+/// A `Resource` is analyzed by its parent scheduler as a task with its budget as cost
+/// and its period as period and deadline.
+impl AsTask for Resource {
+    fn as_task(&self) -> Task {
+        Task::new(self.budget, self.period)
+    }
+}
+
+/// Policies for the access of a component to resources shared with other components.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Sharing {
+    /// _Overrun without payback_, as in Davis and Burns' "Resource sharing in
+    /// hierarchical fixed priority pre-emptive systems" (RTSS, 2006) and in the
+    /// _Bounded-delay Resource Open Environment_ (BROE): a component whose budget is
+    /// exhausted within a critical section keeps executing until it leaves it.
+    Overrun,
+    /// Behnam et al.'s _Subsystem Integration and Resource Allocation Policy_ (SIRAP),
+    /// as described in "SIRAP: a synchronization protocol for hierarchical resource
+    /// sharing in real-time open systems" (EMSOFT, 2007): a task only enters a critical
+    /// section if the remaining budget suffices to complete it, and otherwise idles
+    /// until the next replenishment.
+    Sirap
+}
+
+/// The interface of a component that shares resources with other components.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Interface {
+    /// The resource required by the component.
+    pub resource: Resource,
+    /// The longest time for which the component may execute after its budget is
+    /// exhausted, i.e. its longest critical section under [`Sharing::Overrun`].
+    pub overrun: Time,
+    /// The longest time for which the component holds a shared resource, i.e. its
+    /// longest critical section.
+    pub holding: Time
+}
+
+/// An `Interface` is analyzed by its parent scheduler as its resource, with its cost
+/// increased by the overrun and with the holding time as non-preemptive region, which
+/// accounts for the blocking of other components under the tests in [`lp`].
+impl AsTask for Interface {
+    fn as_task(&self) -> Task {
+        Task::new(self.resource.budget + self.overrun, self.resource.period).with_npr(self.holding)
+    }
+}
+
+/// Processor-demand test for EDF within a component with resource `res`, after Shin
+/// and Lee's "Periodic resource model for compositional real-time guarantees"
+/// (RTSS, 2003): the demand-bound function of the task-set must not exceed the
+/// supply-bound function of the resource.
+///
+/// Release jitter and `blocking` are accounted for as in [`uni::edf`](`crate::bound::uni::edf`),
+/// i.e. `blocking` contains the longest time for which each task can block tasks with
+/// a shorter relative deadline.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn edf(ts: impl IntoIterator<Item: AsTask>, res: &Resource, blocking: Option<&[Time]>) -> bool {
+    let orig = ts.into_iter().map(|t| t.as_task()).collect::<Box<[Task]>>();
+
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), orig.len());
+    }
+
+    if orig.iter().any(|t| t.cost + t.jitter > t.deadline) {
+        return false;
+    }
+
+    let ts = orig.iter()
+                 .map(|t| Task { deadline: t.deadline - t.jitter, jitter: 0, ..*t })
+                 .collect::<Box<_>>();
+
+    let util = ts.iter().fold(RBig::ZERO, |acc, t| {
+        acc + RBig::from(t.cost) / RBig::from(t.period)
+    });
+    let bandwidth = res.bandwidth();
+
+    let Some(max_dl) = orig.iter().map(|t| t.deadline).max() else {
+        return true;
+    };
+
+    // beyond the limit, demand grows no faster than the linear lower bound on supply
+    let limit = if util > bandwidth {
+        return false;
+    } else if util < bandwidth {
+        let slack = ts.iter().fold(RBig::ZERO, |acc, t| {
+            let diff = IBig::from(t.period) - IBig::from(t.deadline);
+            acc + RBig::from(diff.max(IBig::ZERO)) * RBig::from(t.cost) / RBig::from(t.period)
+        });
+        let starve = RBig::from(2 * (res.period - res.budget)) * &bandwidth;
+
+        Time::try_from(((slack + starve) / (bandwidth - util)).ceil()).unwrap_or(Time::MAX)
+    } else {
+        let hyper = task::hyperperiod(ts.iter().map(|t| t.period).chain([res.period]));
+
+        match hyper.and_then(|h| h.checked_add(max_dl)) {
+            Some(l) => l,
+            None => return false
+        }
+    }.max(max_dl);
+
+    let block = |t: Time| blocking.map_or(0, |b| {
+        orig.iter()
+            .zip(b)
+            .filter(|(task, _)| task.deadline > t)
+            .map(|(_, b)| *b)
+            .max()
+            .unwrap_or_default()
+    });
+
+    // walk the deadlines backwards from the limit as in QPA: no deadline before `t` can
+    // fail unless it precedes the shortest interval supplying the demand at `t`, except
+    // below the longest deadline, where blocking requires checking every deadline
+    let low = if blocking.is_some() { max_dl } else { 0 };
+    let mut next = uni::prev_deadline(&ts, limit.saturating_add(1));
+
+    while let Some(t) = next {
+        let demand = uni::dbf(&ts, t);
+
+        if demand + block(t) > res.sbf(t) {
+            return false;
+        }
+
+        next = uni::prev_deadline(&ts, res.time_to_supply(demand).max(low).min(t));
+    }
+
+    true
+}
+
+/// Response-time analysis for fixed-priority scheduling within a component with
+/// resource `res`, after Almeida and Pedreiras' "Scheduling within temporal partitions:
+/// response-time analysis and server design" (EMSOFT, 2004): the response time of each
+/// task is the time the resource takes to supply its request-bound function.
+///
+/// Priorities, `blocking` and release jitter are as for [`uni::fp`](`crate::bound::uni::fp`).
+///
+/// Returns `None` if `ts` is not constrained, and otherwise a bound on the response time
+/// of each task, or `None` for tasks that can miss their deadline.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn fp(
+    ts: impl IntoIterator<Item: AsTask>,
+    res: &Resource,
+    blocking: Option<&[Time]>
+) -> Option<Box<[Option<Time>]>> {
+    let ts = ts.into_iter().map(|t| t.as_task()).collect::<Box<[Task]>>();
+
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), ts.len());
+    }
+
+    if ts.iter().any(|t| t.deadline > t.period) {
+        return None;
+    }
+
+    Some((0 .. ts.len()).map(|k| {
+        let tk = &ts[k];
+        let base = blocking.map_or(0, |b| b[k]) + tk.cost;
+        let mut t = res.time_to_supply(base);
+
+        loop {
+            if t + tk.jitter > tk.deadline {
+                return None;
+            }
+
+            let next = res.time_to_supply(base + (0 .. ts.len()).filter(|i| {
+                *i != k && ts[*i].priority <= tk.priority
+            }).map(|i| ts[i].max_jobs(t) * ts[i].cost).sum::<Time>());
+
+            if next == t {
+                return Some(t + tk.jitter);
+            }
+
+            t = next;
+        }
+    }).collect())
+}
+
+/// Returns the resource with period `period` and least budget no less than `min` that
+/// passes `test`, if any, by binary search over the budget.
+fn synthesize(period: Time, min: Time, test: impl Fn(&Resource) -> bool) -> Option<Resource> {
+    let (mut lo, mut hi) = (min.max(1), period);
+
+    if lo > hi || !test(&Resource::new(hi, period)) {
+        return None;
+    }
+
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+
+        if test(&Resource::new(mid, period)) {
+            hi = mid;
+        } else {
+            lo = mid + 1;
+        }
+    }
+
+    Some(Resource::new(hi, period))
+}
+
+/// Synthesizes the interface of a component scheduled by EDF, i.e. the resource with
+/// period `period` and least budget such that `ts` passes [`edf`] with `blocking`.
+///
+/// Returns `None` if `ts` does not pass the test even on a dedicated CPU.
+///
+/// # Panics
+///
+/// Panics if `period` is zero, or if `blocking` is given and its length differs from
+/// that of `ts`.
+pub fn edf_interface(
+    ts: impl IntoIterator<Item: AsTask>,
+    period: Time,
+    blocking: Option<&[Time]>
+) -> Option<Resource> {
+    let ts = ts.into_iter().map(|t| t.as_task()).collect::<Box<[Task]>>();
+    synthesize(period, 1, |res| edf(&ts, res, blocking))
+}
+
+/// Synthesizes the interface of a component scheduled by fixed priority, i.e. the
+/// resource with period `period` and least budget such that `ts` passes [`fp`] with
+/// `blocking`.
+///
+/// Returns `None` if `ts` is not constrained or does not pass the test even on a
+/// dedicated CPU.
+///
+/// # Panics
+///
+/// Panics if `period` is zero, or if `blocking` is given and its length differs from
+/// that of `ts`.
+pub fn fp_interface(
+    ts: impl IntoIterator<Item: AsTask>,
+    period: Time,
+    blocking: Option<&[Time]>
+) -> Option<Resource> {
+    let ts = ts.into_iter().map(|t| t.as_task()).collect::<Box<[Task]>>();
+
+    synthesize(period, 1, |res| {
+        fp(&ts, res, blocking).is_some_and(|r| r.iter().all(Option::is_some))
+    })
+}
+
+/// Synthesizes the interface of a component scheduled by `policy` whose tasks access
+/// resources shared with other components under `sharing`, where `sections` contains,
+/// for each task, the number of critical sections of each of its jobs and the length
+/// of the longest one.
+///
+/// Critical sections on shared resources are executed non-preemptively within the
+/// component, and hence block local tasks as the non-preemptive regions of [`lp`].
+/// Under [`Sharing::Sirap`], a job may additionally idle before entering each of its
+/// critical sections, for at most the length of that section; this is accounted for by
+/// increasing its cost by the number of its sections times the longest one, and the
+/// budget must be no less than the longest critical section.
+///
+/// Returns `None` if `policy` is [`Policy::Fifo`], if the local test does not support
+/// `ts`, or if `ts` does not pass it even on a dedicated CPU.
+///
+/// # Panics
+///
+/// Panics if `period` is zero, or if the lengths of `ts` and `sections` differ.
+pub fn shared_interface(
+    ts: impl IntoIterator<Item: AsTask>,
+    sections: &[Request],
+    period: Time,
+    policy: Policy,
+    sharing: Sharing
+) -> Option<Interface> {
+    let mut ts = ts.into_iter().map(|t| t.as_task()).collect::<Box<[Task]>>();
+    assert_eq!(sections.len(), ts.len());
+
+    let longest = sections.iter()
+                          .map(|cs| if cs.num > 0 { cs.length } else { 0 })
+                          .collect::<Box<_>>();
+    let holding = longest.iter().copied().max().unwrap_or_default();
+
+    let (min, overrun) = match sharing {
+        Sharing::Overrun => (1, holding),
+        Sharing::Sirap   => {
+            for (t, cs) in ts.iter_mut().zip(sections) {
+                t.cost += cs.num as Time * cs.length;
+            }
+
+            (holding, 0)
+        }
+    };
+
+    let resource = match policy {
+        Policy::Edf   => synthesize(period, min, |res| edf(&ts, res, Some(&longest))),
+        Policy::Fixed => {
+            let block = lp::blocking(&ts, &longest, Policy::Fixed, 1);
+
+            synthesize(period, min, |res| {
+                fp(&ts, res, Some(&block)).is_some_and(|r| r.iter().all(Option::is_some))
+            })
+        },
+        Policy::Fifo  => None
+    }?;
+
+    Some(Interface { resource, overrun, holding })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tasks() -> [Task; 2] {
+        [Task::new(1, 10), Task { priority: 1, ..Task::new(2, 20) }]
+    }
+
+    #[test]
+    fn supply() {
+        // Shin and Lee's resource (5, 2) starves for up to 2 (5 - 2) = 6 time units
+        let res = Resource::new(2, 5);
+        let sbf = (0 ..= 13).map(|t| res.sbf(t)).collect::<Vec<_>>();
+
+        assert_eq!(sbf, [0, 0, 0, 0, 0, 0, 0, 1, 2, 2, 2, 2, 3, 4]);
+        assert_eq!((res.time_to_supply(0), res.time_to_supply(1), res.time_to_supply(3)), (0, 7, 12));
+
+        for work in 1 .. 20 {
+            let t = res.time_to_supply(work);
+            assert!(res.sbf(t) >= work && res.sbf(t - 1) < work);
+        }
+
+        assert_eq!(Resource::new(5, 5).sbf(7), 7);
+    }
+
+    #[test]
+    fn local_tests() {
+        let ts = tasks();
+        let res = Resource::new(2, 5);
+
+        assert!(edf(ts, &res, None));
+        assert_eq!(fp(ts, &res, None).as_deref(), Some([Some(7), Some(13)].as_slice()));
+        assert!(!edf(ts, &Resource::new(1, 5), None));
+        assert_eq!(fp([Task::new(1, 10).with_deadline(20)], &res, None), None);
+
+        // utilization just below the bandwidth puts the limit near 10^9
+        let tight = [Task::new(1, 4), Task::new(249_999_999, 1_000_000_000)];
+        assert!(edf(tight, &Resource::new(1, 2), None));
+    }
+
+    #[test]
+    fn interfaces() {
+        let ts = tasks();
+
+        assert_eq!(edf_interface(ts, 5, None), Some(Resource::new(2, 5)));
+        assert_eq!(fp_interface(ts, 5, None), Some(Resource::new(2, 5)));
+        assert_eq!(edf_interface([Task::new(9, 10).with_deadline(8)], 5, None), None);
+    }
+
+    #[test]
+    fn sirap_sections() {
+        let ts = tasks();
+        let sections = |num| [Request::default(), Request { num, length: 1 }];
+
+        let overrun = shared_interface(ts, &sections(3), 5, Policy::Edf, Sharing::Overrun);
+        assert_eq!(overrun, Some(Interface { resource: Resource::new(2, 5), overrun: 1, holding: 1 }));
+
+        // under SIRAP, a job may idle before each of its critical sections
+        for policy in [Policy::Edf, Policy::Fixed] {
+            let one = shared_interface(ts, &sections(1), 5, policy, Sharing::Sirap);
+            let three = shared_interface(ts, &sections(3), 5, policy, Sharing::Sirap);
+
+            assert_eq!(one.map(|i| i.resource), Some(Resource::new(2, 5)));
+            assert_eq!(three.map(|i| i.resource), Some(Resource::new(3, 5)));
+        }
+
+        assert_eq!(shared_interface(ts, &sections(1), 5, Policy::Fifo, Sharing::Sirap), None);
+    }
+}
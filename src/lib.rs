@@ -4,3 +4,15 @@ pub mod sharing;
 pub mod gen;
 pub mod proto;
 pub mod bound;
+pub mod partition;
+pub mod prio;
+pub mod suspend;
+pub mod mc;
+pub mod dag;
+pub mod gang;
+pub mod gmf;
+pub mod arrival;
+pub mod tt;
+pub mod platform;
+pub mod hier;
+pub mod sens;
@@ -1,14 +1,16 @@
 #![warn(clippy::pedantic)]
 
 use rtsched::{
-    task::{Task, Time},
+    task::{Task, AsTask, Time, Policy},
     rsrc::{System, Mutex, Rw},
     sharing::{ObliviousAnalyzer, ObliviousTask},
     proto::{
         GlobalOm, SingleClusterOm,
         OptimalFIFO, FlexibleMulti
     },
-    gen, bound
+    partition::{Partitioner, Fit, Order},
+    bound::{self, lp},
+    gen, prio
 };
 
 use clap::{Parser, ValueEnum};
@@ -77,9 +79,30 @@ trait Statistic {
     fn describe(&self, w: &mut impl fmt::Write, res: &Self::Output, count: usize) -> fmt::Result;
 }
 
+// packs tasks into clusters using first-fit decreasing, ignoring blocking
+fn clusters(tasks: &[Task], num_cpus: usize, cluster_size: usize) -> Option<Box<[Box<[Task]>]>> {
+    if cluster_size >= num_cpus {
+        return Some(Box::new([tasks.into()]));
+    }
+
+    let assignment = Partitioner::new(num_cpus, Fit::First, Order::Utilization)
+                     .run_clustered(tasks, cluster_size, |ts: &[Task], m| bound::bounded(ts, m))
+                     .ok()?;
+
+    Some((0 .. assignment.num_clusters()).map(|cl| {
+        assignment.tasks_in(cl).map(|i| tasks[i]).collect()
+    }).collect())
+}
+
+// number of resources of cluster `cluster`, splitting `num_rsrc` evenly among all clusters
+fn cluster_rsrc(num_rsrc: usize, num_clusters: usize, cluster: usize) -> usize {
+    num_rsrc / num_clusters + usize::from(cluster < num_rsrc % num_clusters)
+}
+
 struct MutexS<'a, R1, R2> {
     protos: &'a [Box<dyn ObliviousAnalyzer<Mutex> + Sync>],
     num_cpus: usize,
+    cluster_size: usize,
     num_rsrc: usize,
     requests: &'a gen::Requests<R1, R2>
 }
@@ -93,17 +116,30 @@ impl<R1, R2> Statistic for MutexS<'_, R1, R2> where R1: SampleRange<usize> + Clo
     }
 
     fn collect(&self, tasks: &[Task], res: &mut Self::Output) {
-        let mut sys = System::new(tasks);
+        let Some(clusters) = clusters(tasks, self.num_cpus, self.cluster_size) else {
+            return;
+        };
 
-        self.requests.gen(&mut sys, self.num_rsrc);
+        let mut ok = vec![true; self.protos.len()];
 
-        for (i, proto) in self.protos.iter().enumerate() {
-            let data = sys.run::<dyn ObliviousAnalyzer<Mutex> + Sync>(proto.as_ref());
+        // resources are local to each cluster
+        for (cl, cluster) in clusters.iter().enumerate() {
+            let mut sys = System::new(cluster);
+
+            self.requests.gen(&mut sys, cluster_rsrc(self.num_rsrc, clusters.len(), cl));
+
+            for (i, proto) in self.protos.iter().enumerate() {
+                let data = sys.run::<dyn ObliviousAnalyzer<Mutex> + Sync>(proto.as_ref());
+
+                ok[i] &= bound::bounded(
+                    cluster.iter().zip(data.iter()).map(ObliviousTask::from),
+                    self.cluster_size
+                );
+            }
+        }
 
-            res[i] += usize::from(bound::soft(
-                tasks.iter().zip(data.iter()).map(ObliviousTask::from),
-                self.num_cpus
-            ).unwrap());
+        for (res, ok) in res.iter_mut().zip(ok) {
+            *res += usize::from(ok);
         }
     }
 
@@ -141,6 +177,7 @@ struct RwS<'a, R1, R2> {
     mutex_protos: &'a [Box<dyn ObliviousAnalyzer<Mutex> + Sync>],
     protos:       &'a [Box<dyn ObliviousAnalyzer<Rw> + Sync>],
     num_cpus: usize,
+    cluster_size: usize,
     num_rsrc: usize,
     requests: &'a gen::Requests<R1, R2>,
     prob_write: f64
@@ -155,28 +192,41 @@ impl<R1, R2> Statistic for RwS<'_, R1, R2> where R1: SampleRange<usize> + Clone,
     }
 
     fn collect(&self, tasks: &[Task], res: &mut Self::Output) {
-        let mut sys = System::new(tasks);
+        let Some(clusters) = clusters(tasks, self.num_cpus, self.cluster_size) else {
+            return;
+        };
 
-        self.requests.gen_with(&mut sys, self.num_rsrc, self.prob_write);
+        let mut ok = vec![true; self.mutex_protos.len() + self.protos.len()];
 
-        for (i, proto) in self.protos.iter().enumerate() {
-            let data = sys.run::<dyn ObliviousAnalyzer<Rw> + Sync>(proto.as_ref());
+        // resources are local to each cluster
+        for (cl, cluster) in clusters.iter().enumerate() {
+            let mut sys = System::new(cluster);
 
-            res[self.mutex_protos.len() + i] += usize::from(bound::soft(
-                tasks.iter().zip(data.iter()).map(ObliviousTask::from),
-                self.num_cpus
-            ).unwrap());
-        }
+            self.requests.gen_with(&mut sys, cluster_rsrc(self.num_rsrc, clusters.len(), cl), self.prob_write);
 
-        let sys = sys.as_mutex();
+            for (i, proto) in self.protos.iter().enumerate() {
+                let data = sys.run::<dyn ObliviousAnalyzer<Rw> + Sync>(proto.as_ref());
 
-        for (i, proto) in self.mutex_protos.iter().enumerate() {
-            let data = sys.run::<dyn ObliviousAnalyzer<Mutex> + Sync>(proto.as_ref());
+                ok[self.mutex_protos.len() + i] &= bound::bounded(
+                    cluster.iter().zip(data.iter()).map(ObliviousTask::from),
+                    self.cluster_size
+                );
+            }
+
+            let sys = sys.as_mutex();
 
-            res[i] += usize::from(bound::soft(
-                tasks.iter().zip(data.iter()).map(ObliviousTask::from),
-                self.num_cpus
-            ).unwrap());
+            for (i, proto) in self.mutex_protos.iter().enumerate() {
+                let data = sys.run::<dyn ObliviousAnalyzer<Mutex> + Sync>(proto.as_ref());
+
+                ok[i] &= bound::bounded(
+                    cluster.iter().zip(data.iter()).map(ObliviousTask::from),
+                    self.cluster_size
+                );
+            }
+        }
+
+        for (res, ok) in res.iter_mut().zip(ok) {
+            *res += usize::from(ok);
         }
     }
 
@@ -188,7 +238,7 @@ impl<R1, R2> Statistic for RwS<'_, R1, R2> where R1: SampleRange<usize> + Clone,
                 write!(w, "\t{}", proto.name())?;
             }
 
-            &self.protos
+            self.protos
         } else {
             let Some((first, protos)) = self.protos.split_first() else {
                 return Ok(());
@@ -221,6 +271,88 @@ impl<R1, R2> Statistic for RwS<'_, R1, R2> where R1: SampleRange<usize> + Clone,
     }
 }
 
+// tasks of `sys` with costs inflated by their total blocking under `proto`, and longest
+// non-preemptive regions including their arrival bounds
+fn lp_tasks(sys: &System<'_, Mutex>, proto: &(dyn ObliviousAnalyzer<Mutex> + Sync)) -> Box<[Task]> {
+    let data = sys.run::<dyn ObliviousAnalyzer<Mutex> + Sync>(proto);
+    let regions = lp::regions(sys.tasks(), Some(&data));
+
+    sys.tasks().iter().zip(data.iter()).zip(regions).map(|((t, d), npr)| {
+        Task { npr, ..ObliviousTask::from((t, d)).as_task() }
+    }).collect()
+}
+
+// whether `tasks` can be partitioned onto `num_cpus` CPUs under limited-preemptive EDF
+// and fixed-priority scheduling, where each task is blocked by the non-preemptive regions
+// of the other tasks on its CPU
+fn partitioned(tasks: &[Task], num_cpus: usize) -> [bool; 2] {
+    let part = Partitioner::new(num_cpus, Fit::First, Order::Utilization);
+
+    let edf = |ts: &[Task]| lp::edf(ts, &lp::regions(ts, None));
+    let fp = |ts: &[Task]| lp::fp(ts, &lp::regions(ts, None)).iter().all(Option::is_some);
+
+    [part.run(tasks, edf).is_ok(), part.run(tasks, fp).is_ok()]
+}
+
+struct PartS<'a, R1, R2> {
+    protos: &'a [Box<dyn ObliviousAnalyzer<Mutex> + Sync>],
+    num_cpus: usize,
+    num_rsrc: usize,
+    requests: &'a gen::Requests<R1, R2>
+}
+
+impl<R1, R2> Statistic for PartS<'_, R1, R2> where R1: SampleRange<usize> + Clone,
+                                                   R2: SampleRange<Time> + Clone {
+    type Output = Box<[usize]>;
+
+    fn new_result(&self) -> Self::Output {
+        vec![0; 2 * self.protos.len()].into_boxed_slice()
+    }
+
+    fn collect(&self, tasks: &[Task], res: &mut Self::Output) {
+        let mut tasks = tasks.to_vec();
+        prio::dm(&mut tasks);
+
+        // resources are shared by all CPUs
+        let mut sys = System::new(&tasks);
+        self.requests.gen(&mut sys, self.num_rsrc);
+
+        for (i, proto) in self.protos.iter().enumerate() {
+            let [edf, fp] = partitioned(&lp_tasks(&sys, proto.as_ref()), self.num_cpus);
+
+            res[2 * i] += usize::from(edf);
+            res[2 * i + 1] += usize::from(fp);
+        }
+    }
+
+    fn header(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        for (i, proto) in self.protos.iter().enumerate() {
+            if i > 0 {
+                write!(w, "\t")?;
+            }
+
+            write!(w, "P-EDF/{}\tP-FP/{}", proto.name(), proto.name())?;
+        }
+
+        Ok(())
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn describe(&self, w: &mut impl fmt::Write, res: &Self::Output, count: usize) -> fmt::Result {
+        let Some((first, res)) = res.split_first() else {
+            return Ok(());
+        };
+
+        write!(w, "{}", *first as f64 / count as f64)?;
+
+        for value in res {
+            write!(w, "\t{}", *value as f64 / count as f64)?;
+        }
+
+        Ok(())
+    }
+}
+
 struct StatRunner<'a, S> {
     stat: S,
     periods: RangeInclusive<Time>,
@@ -273,8 +405,11 @@ struct SingleRunArgs {
     #[arg(value_enum, short = 'p')]
     /// Task period length class
     periods: Length,
+    #[arg(short = 'c')]
+    /// Number of CPUs in each cluster, which must divide the number of CPUs [default: all CPUs]
+    cluster_size: Option<usize>,
     #[arg(short = 'r')]
-    /// Number of resources in system
+    /// Number of resources in system, split evenly among clusters if clustered
     num_rsrc: usize,
     #[arg(short = 'a')]
     /// Probability of access (indep. for each task/resource pair)
@@ -287,6 +422,17 @@ struct SingleRunArgs {
     log_nuf: bool
 }
 
+impl SingleRunArgs {
+    fn cluster_size(&self) -> usize {
+        let cluster_size = self.cluster_size.unwrap_or(self.num_cpus);
+
+        assert!(cluster_size > 0 && self.num_cpus.is_multiple_of(cluster_size),
+                "cluster size must divide the number of CPUs");
+
+        cluster_size
+    }
+}
+
 #[derive(Parser)]
 #[command(version)]
 enum TestSet {
@@ -303,6 +449,12 @@ enum TestSet {
         /// Probability that an access will be counted as write
         prob_write: f64
     },
+    /// Test spin-based protocols for mutex access under partitioned limited-preemptive
+    /// EDF and FP, with deadline-monotonic priorities
+    Partitioned {
+        #[command(flatten)]
+        args: SingleRunArgs
+    },
     /// Generate valid combinations for all mutex protocols in current directory
     MutexAll,
     /// Generate valid combinations for all read-write protocols in current directory
@@ -329,13 +481,19 @@ impl fmt::Display for Length {
 
 fn mutex_list(num_cpus: usize) -> Box<[Box<dyn ObliviousAnalyzer<Mutex> + Sync>]> {
     Box::new([Box::new(GlobalOm::new(num_cpus)),
-              Box::new(SingleClusterOm::new(num_cpus)),
+              Box::new(SingleClusterOm::new(num_cpus).with_policy(Policy::Edf)),
               Box::new(OptimalFIFO::new(num_cpus)),
               Box::new(FlexibleMulti)])
 }
 
+// protocols whose analysis also holds for partitioned scheduling, where their arrival
+// bounds are non-preemptive regions blocking tasks on the same CPU
+fn partitioned_list() -> Box<[Box<dyn ObliviousAnalyzer<Mutex> + Sync>]> {
+    Box::new([Box::new(FlexibleMulti)])
+}
+
 fn rw_list_rw(num_cpus: usize) -> Box<[Box<dyn ObliviousAnalyzer<Rw> + Sync>]> {
-    Box::new([Box::new(SingleClusterOm::new(num_cpus)),
+    Box::new([Box::new(SingleClusterOm::new(num_cpus).with_policy(Policy::Edf)),
               Box::new(OptimalFIFO::new(num_cpus))])
 }
 
@@ -360,6 +518,7 @@ fn runner_from_args<S>(args: &SingleRunArgs, stat: S) where S: Statistic + Sync,
     });
 }
 
+#[allow(clippy::too_many_lines)]
 fn main() {
     const NUM_CPUS:    [usize; 3]  = [4, 8, 16];
     // also period
@@ -378,9 +537,12 @@ fn main() {
                 LENGTHS[args.lengths as usize].clone()
             );
 
+            let cluster_size = args.cluster_size();
+
             runner_from_args(&args, MutexS {
-                protos: &mutex_list(args.num_cpus),
+                protos: &mutex_list(cluster_size),
                 num_cpus: args.num_cpus,
+                cluster_size,
                 num_rsrc: args.num_rsrc,
                 requests: &requests
             });
@@ -393,16 +555,36 @@ fn main() {
                 LENGTHS[args.lengths as usize].clone()
             );
 
+            let cluster_size = args.cluster_size();
+
             runner_from_args(&args, RwS {
-                mutex_protos: &rw_list_mutex(args.num_cpus),
-                protos: &rw_list_rw(args.num_cpus),
+                mutex_protos: &rw_list_mutex(cluster_size),
+                protos: &rw_list_rw(cluster_size),
                 num_cpus: args.num_cpus,
+                cluster_size,
                 num_rsrc: args.num_rsrc,
                 requests: &requests,
                 prob_write
             });
         },
 
+        TestSet::Partitioned { args } => {
+            assert!(args.cluster_size.is_none(), "partitioned runs do not take a cluster size");
+
+            let requests = gen::Requests::new(
+                args.prob_acc,
+                1 ..= 5,
+                LENGTHS[args.lengths as usize].clone()
+            );
+
+            runner_from_args(&args, PartS {
+                protos: &partitioned_list(),
+                num_cpus: args.num_cpus,
+                num_rsrc: args.num_rsrc,
+                requests: &requests
+            });
+        },
+
         TestSet::MutexAll => {
             let nuf = Nuf::uniform();
 
@@ -428,6 +610,7 @@ fn main() {
                         let stat = MutexS {
                             protos: &protos,
                             requests: &requests,
+                            cluster_size: num_cpus,
                             num_cpus, num_rsrc
                         };
 
@@ -445,7 +628,7 @@ fn main() {
                             Err(e) => panic!("error while creating {out_name}: {e}")
                         };
 
-                        if let Err(e) = write!(file, "{}", runner) {
+                        if let Err(e) = write!(file, "{runner}") {
                             panic!("error while writing {out_name}: {e}");
                         }
                     }
@@ -480,6 +663,7 @@ fn main() {
                             mutex_protos: &mutex_protos,
                             protos: &protos,
                             requests: &requests,
+                            cluster_size: num_cpus,
                             num_cpus, num_rsrc, prob_write
                         };
 
@@ -497,7 +681,7 @@ fn main() {
                             Err(e) => panic!("error while creating {out_name}: {e}")
                         };
 
-                        if let Err(e) = write!(file, "{}", runner) {
+                        if let Err(e) = write!(file, "{runner}") {
                             panic!("error while writing {out_name}: {e}");
                         }
                     }
@@ -506,3 +690,56 @@ fn main() {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use rtsched::{rsrc::Request, partition::Edf};
+
+    /// A constrained task without requests, and a task with a long request whose
+    /// non-preemptive execution exceeds the slack of the former.
+    fn system(tasks: &[Task]) -> System<'_, Mutex> {
+        let mut sys = System::new(tasks);
+        let rsrc = sys.add_rsrc();
+
+        sys.add_req(1, rsrc, Request { num: 1, length: 3 });
+        sys
+    }
+
+    #[test]
+    fn arrival_blocking() {
+        let mut tasks = [Task::new(1, 4).with_deadline(2), Task::new(4, 20)];
+        prio::dm(&mut tasks);
+
+        let sys = system(&tasks);
+        let lp = lp_tasks(&sys, &FlexibleMulti);
+
+        assert_eq!(lp.iter().map(|t| (t.cost, t.npr)).collect::<Vec<_>>(), [(1, 0), (4, 3)]);
+
+        // the tasks only fit on one CPU if the region is ignored
+        assert!(Partitioner::new(1, Fit::First, Order::Utilization).run(&lp, Edf).is_ok());
+        assert_eq!(partitioned(&lp, 1), [false, false]);
+        assert_eq!(partitioned(&lp, 2), [true, true]);
+    }
+
+    #[test]
+    fn spinning() {
+        // each task spins for the request of the other one, which inflates its cost
+        let mut tasks = [Task::new(4, 10), Task::new(4, 10)];
+        prio::dm(&mut tasks);
+
+        let mut sys = System::new(&tasks);
+        let rsrc = sys.add_rsrc();
+
+        sys.add_req(0, rsrc, Request { num: 1, length: 1 });
+        sys.add_req(1, rsrc, Request { num: 1, length: 2 });
+
+        let lp = lp_tasks(&sys, &FlexibleMulti);
+
+        assert_eq!(lp.iter().map(|t| (t.cost, t.npr)).collect::<Vec<_>>(), [(6, 3), (5, 3)]);
+        assert_eq!(partitioned(&tasks, 1), [true, true]);
+        assert_eq!(partitioned(&lp, 1), [false, false]);
+        assert_eq!(partitioned(&lp, 2), [true, true]);
+    }
+}
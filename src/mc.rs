@@ -0,0 +1,322 @@
+//! The dual-criticality task model.
+//!
+//! Each task has a criticality level and a WCET for each level: the LO-criticality
+//! WCET is its [`cost`](`Task::cost`), while HI-criticality tasks also have a larger,
+//! more pessimistic HI-criticality WCET. The system starts in LO mode and switches to
+//! HI mode as soon as a job of a HI-criticality task executes for longer than its
+//! LO-criticality WCET; from then on, LO-criticality tasks are no longer guaranteed.
+//!
+//! The tests in this module are uniprocessor tests, which also implement [`UniTest`]
+//! for use with a [`Partitioner`](`crate::partition::Partitioner`) to obtain
+//! partitioned mixed-criticality scheduling.
+
+use crate::{
+    task::{Task, AsTask, Time},
+    partition::UniTest
+};
+
+use dashu::rational::RBig;
+
+/// A criticality level.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum Criticality {
+    /// Low criticality.
+    Lo,
+    /// High criticality.
+    Hi
+}
+
+/// A dual-criticality task.
+#[derive(Clone, Copy)]
+pub struct McTask {
+    /// The task, with its LO-criticality WCET as cost.
+    pub task: Task,
+    /// The task's criticality level.
+    pub crit: Criticality,
+    /// The task's HI-criticality WCET; equal to the LO-criticality WCET for
+    /// LO-criticality tasks.
+    pub hi_cost: Time
+}
+
+impl McTask {
+    /// Constructs a new LO-criticality `McTask` from `task`.
+    pub fn lo(task: Task) -> Self {
+        Self { task, crit: Criticality::Lo, hi_cost: task.cost }
+    }
+
+    /// Constructs a new HI-criticality `McTask` from `task`, with HI-criticality
+    /// WCET `hi_cost`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `hi_cost` is less than the cost of `task`.
+    pub fn hi(task: Task, hi_cost: Time) -> Self {
+        assert!(hi_cost >= task.cost);
+        Self { task, crit: Criticality::Hi, hi_cost }
+    }
+
+    /// Returns the WCET of the task at criticality level `level`.
+    pub fn cost(&self, level: Criticality) -> Time {
+        match level {
+            Criticality::Lo => self.task.cost,
+            Criticality::Hi => self.hi_cost
+        }
+    }
+}
+
+/// An `McTask` is analyzed as its task with the WCET of its own criticality level.
+impl AsTask for McTask {
+    fn as_task(&self) -> Task {
+        Task { cost: self.cost(self.crit), ..self.task }
+    }
+}
+
+/// Iterates `step` starting from `start` until it reaches a fixed point; returns
+/// `None` if the iteration exceeds `deadline`.
+fn fixed_point(start: Time, deadline: Time, step: impl Fn(Time) -> Time) -> Option<Time> {
+    let mut r = start;
+
+    loop {
+        if r > deadline {
+            return None;
+        }
+
+        let next = step(r);
+
+        if next == r {
+            return Some(r);
+        }
+
+        r = next;
+    }
+}
+
+/// Returns the indices of the tasks of `ts` that may interfere with the task at index
+/// `k` under fixed-priority scheduling, split into LO- and HI-criticality tasks.
+fn hp(ts: &[McTask], k: usize) -> (Vec<usize>, Vec<usize>) {
+    (0 .. ts.len()).filter(|i| *i != k && ts[*i].task.priority <= ts[k].task.priority)
+                   .partition(|i| ts[*i].crit == Criticality::Lo)
+}
+
+/// Returns the response-time bound of the task at index `k` of `ts` in LO mode, if
+/// it meets its deadline.
+fn lo_response(ts: &[McTask], k: usize) -> Option<Time> {
+    let tk = &ts[k].task;
+
+    fixed_point(tk.cost, tk.deadline, |r| {
+        tk.cost + (0 .. ts.len()).filter(|i| *i != k && ts[*i].task.priority <= tk.priority)
+                                 .map(|i| r.div_ceil(ts[i].task.period) * ts[i].task.cost)
+                                 .sum::<Time>()
+    })
+}
+
+/// Runs `hi_response` on each HI-criticality task of `ts`, after checking that
+/// every task meets its deadline in LO mode.
+///
+/// Returns `None` if `ts` is not constrained or has release jitter.
+fn amc(
+    ts: &[McTask],
+    hi_response: impl Fn(usize, Time) -> Option<Time>
+) -> Option<Box<[Option<Time>]>> {
+    if ts.iter().any(|t| t.task.deadline > t.task.period || t.task.jitter > 0) {
+        return None;
+    }
+
+    Some((0 .. ts.len()).map(|k| {
+        let lo = lo_response(ts, k)?;
+
+        match ts[k].crit {
+            Criticality::Lo => Some(lo),
+            Criticality::Hi => hi_response(k, lo).map(|hi| hi.max(lo))
+        }
+    }).collect())
+}
+
+/// Baruah, Burns and Davis' _Adaptive Mixed Criticality_ test with response-time
+/// bounds (AMC-rtb) for uniprocessor fixed-priority scheduling, as described in
+/// "Response-time analysis for mixed criticality systems" (RTSS, 2011).
+///
+/// Priorities are given by [`Task::priority`], with lower values meaning higher
+/// priority and ties interfering with each other.
+///
+/// Returns `None` if `ts` is not constrained or has release jitter, and otherwise a
+/// bound on the response time of each task in any mode in which it is guaranteed, or
+/// `None` for tasks that cannot be shown to meet their deadline.
+pub fn amc_rtb(ts: &[McTask]) -> Option<Box<[Option<Time>]>> {
+    amc(ts, |k, lo| {
+        let (hpl, hph) = hp(ts, k);
+        let tk = &ts[k];
+
+        // LO-criticality tasks can only interfere before the mode switch
+        let before = hpl.iter()
+                        .map(|i| lo.div_ceil(ts[*i].task.period) * ts[*i].task.cost)
+                        .sum::<Time>();
+
+        fixed_point(tk.hi_cost, tk.task.deadline, |r| {
+            tk.hi_cost + before + hph.iter()
+                                     .map(|j| r.div_ceil(ts[*j].task.period) * ts[*j].hi_cost)
+                                     .sum::<Time>()
+        })
+    })
+}
+
+/// Returns the largest number of jobs of `task` that can be released in an interval
+/// of length `t` after the mode switch at time `s`, in the analysis of AMC-max.
+fn amc_max_jobs(task: &Task, s: Time, t: Time) -> Time {
+    let total = t.div_ceil(task.period);
+    let x = i128::from(t) - i128::from(s) - i128::from(task.period - task.deadline);
+    let period = i128::from(task.period);
+
+    // ceiling division, also for negative values
+    let after = if x >= 0 { (x + period - 1) / period } else { -(-x / period) } + 1;
+
+    Time::try_from(after.max(0)).unwrap_or_default().min(total)
+}
+
+/// Baruah, Burns and Davis' _Adaptive Mixed Criticality_ test with maximization over
+/// the time of the mode switch (AMC-max) for uniprocessor fixed-priority scheduling,
+/// as described in "Response-time analysis for mixed criticality systems" (RTSS, 2011).
+///
+/// This test dominates [`amc_rtb`]; priorities and results are as for [`amc_rtb`].
+pub fn amc_max(ts: &[McTask]) -> Option<Box<[Option<Time>]>> {
+    amc(ts, |k, lo| {
+        let (hpl, hph) = hp(ts, k);
+        let tk = &ts[k];
+
+        // the mode switch can be assumed to happen at a release of a LO-criticality task
+        let mut points = hpl.iter()
+                            .flat_map(|i| (0 .. lo).step_by(ts[*i].task.period as usize))
+                            .collect::<Vec<_>>();
+        points.push(0);
+        points.sort_unstable();
+        points.dedup();
+
+        points.into_iter().map(|s| {
+            let before = hpl.iter()
+                            .map(|i| (s / ts[*i].task.period + 1) * ts[*i].task.cost)
+                            .sum::<Time>();
+
+            fixed_point(tk.hi_cost, tk.task.deadline, |t| {
+                tk.hi_cost + before + hph.iter().map(|j| {
+                    let tj = &ts[*j];
+                    let after = amc_max_jobs(&tj.task, s, t);
+
+                    after * tj.hi_cost + (t.div_ceil(tj.task.period) - after) * tj.task.cost
+                }).sum::<Time>()
+            })
+        }).try_fold(0, |acc, r| r.map(|r| r.max(acc)))
+    })
+}
+
+/// Baruah et al.'s EDF with virtual deadlines (EDF-VD) test for implicit-deadline
+/// tasks, as described in "The preemptive uniprocessor scheduling of mixed-criticality
+/// implicit-deadline sporadic task systems" (ECRTS, 2012).
+///
+/// In LO mode, each HI-criticality task is scheduled by EDF with its relative deadline
+/// scaled by a factor `x`, and restored upon the mode switch.
+///
+/// Returns `x` if `ts` is schedulable, with `x = 1` if plain EDF suffices, or `None`
+/// if `ts` is not implicit or cannot be shown to be schedulable.
+pub fn edf_vd(ts: &[McTask]) -> Option<RBig> {
+    if ts.iter().any(|t| t.task.deadline != t.task.period || t.task.jitter > 0) {
+        return None;
+    }
+
+    let util = |crit, level| ts.iter().filter(|t| t.crit == crit).fold(RBig::ZERO, |acc, t| {
+        acc + RBig::from(t.cost(level)) / RBig::from(t.task.period)
+    });
+
+    let lo_lo = util(Criticality::Lo, Criticality::Lo);
+    let hi_lo = util(Criticality::Hi, Criticality::Lo);
+    let hi_hi = util(Criticality::Hi, Criticality::Hi);
+
+    if &lo_lo + &hi_hi <= RBig::ONE {
+        return Some(RBig::ONE);
+    } else if lo_lo >= RBig::ONE {
+        return None;
+    }
+
+    let x = hi_lo / (RBig::ONE - &lo_lo);
+    (&x * lo_lo + hi_hi <= RBig::ONE).then_some(x)
+}
+
+/// Uniprocessor AMC scheduling, tested using [`amc_rtb`].
+pub struct AmcRtb;
+
+impl UniTest<McTask> for AmcRtb {
+    fn accepts(&self, tasks: &[McTask]) -> bool {
+        amc_rtb(tasks).is_some_and(|r| r.iter().all(Option::is_some))
+    }
+}
+
+/// Uniprocessor AMC scheduling, tested using [`amc_max`].
+pub struct AmcMax;
+
+impl UniTest<McTask> for AmcMax {
+    fn accepts(&self, tasks: &[McTask]) -> bool {
+        amc_max(tasks).is_some_and(|r| r.iter().all(Option::is_some))
+    }
+}
+
+/// Uniprocessor EDF-VD scheduling, tested using [`edf_vd`].
+pub struct EdfVd;
+
+impl UniTest<McTask> for EdfVd {
+    fn accepts(&self, tasks: &[McTask]) -> bool {
+        edf_vd(tasks).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn task(cost: Time, period: Time, priority: u64) -> Task {
+        Task { priority, ..Task::new(cost, period) }
+    }
+
+    /// A LO-criticality task with high utilization and two HI-criticality tasks, for
+    /// which the lowest-priority one is only deemed schedulable by AMC-max.
+    fn system() -> [McTask; 3] {
+        [McTask::lo(task(3, 4, 0)),
+         McTask::hi(task(1, 12, 1), 4),
+         McTask::hi(task(3, 30, 2), 4)]
+    }
+
+    #[test]
+    fn amc_rtb_vs_max() {
+        let ts = system();
+
+        // in LO mode, the lowest-priority task completes by 20; after the mode switch,
+        // AMC-rtb counts all 5 jobs of the LO-criticality task released until then
+        assert_eq!(amc_rtb(&ts).as_deref(), Some([Some(3), Some(7), None].as_slice()));
+
+        // the worst case for AMC-max is the mode switch at time 16
+        assert_eq!(amc_max(&ts).as_deref(), Some([Some(3), Some(7), Some(28)].as_slice()));
+
+        assert!(!AmcRtb.accepts(&ts));
+        assert!(AmcMax.accepts(&ts));
+    }
+
+    #[test]
+    fn amc_lo_mode() {
+        let ts = [McTask::lo(task(2, 4, 0)), McTask::hi(task(2, 4, 1), 3)];
+
+        // the CPU is fully used in LO mode, leaving no room for any overrun
+        assert_eq!(amc_max(&ts).as_deref(), Some([Some(2), None].as_slice()));
+        assert_eq!(amc_rtb([McTask::lo(task(1, 4, 0).with_deadline(5))].as_slice()), None);
+    }
+
+    #[test]
+    fn edf_virtual_deadlines() {
+        let lo = McTask::lo(Task::new(5, 10));
+
+        assert_eq!(edf_vd(&[McTask::lo(Task::new(3, 10)), McTask::hi(Task::new(2, 10), 6)]), Some(RBig::ONE));
+
+        // the HI-criticality task's deadline is scaled by 0.2 / (1 - 0.5)
+        let x = RBig::from(2) / RBig::from(5);
+        assert_eq!(edf_vd(&[lo, McTask::hi(Task::new(2, 10), 6)]), Some(x));
+        assert_eq!(edf_vd(&[lo, McTask::hi(Task::new(2, 10), 9)]), None);
+        assert_eq!(edf_vd(&[McTask::lo(Task::new(1, 10).with_deadline(5))]), None);
+    }
+}
@@ -0,0 +1,506 @@
+//! Partitioning of task-sets onto CPUs and clusters of CPUs.
+//!
+//! Tasks are placed one at a time by a bin-packing heuristic; every candidate
+//! placement is validated by running a test on the tasks that would be assigned
+//! to the candidate cluster. Partitioned scheduling is the special case of clusters
+//! made of a single CPU, where a uniprocessor test is used; global scheduling is
+//! the special case of a single cluster made of all CPUs.
+
+use crate::{
+    task::{Task, AsTask, Time},
+    bound::uni
+};
+
+use dashu::rational::RBig;
+
+/// Heuristic used to pick a cluster among those that can accept a task.
+#[derive(Clone, Copy)]
+pub enum Fit {
+    /// Picks the cluster with the lowest index.
+    First,
+    /// Picks the cluster with the highest utilization per CPU, i.e. the one that would
+    /// have the least capacity left.
+    Best,
+    /// Picks the cluster with the lowest utilization per CPU, i.e. the one that would
+    /// have the most capacity left.
+    Worst
+}
+
+/// Order in which tasks are placed.
+#[derive(Clone, Copy)]
+pub enum Order {
+    /// Decreasing order of utilization.
+    Utilization,
+    /// Decreasing order of density, using the minimum of deadline and period.
+    Density
+}
+
+/// A uniprocessor test deciding whether a set of tasks can share a CPU.
+pub trait UniTest<T> {
+    /// Tests whether `tasks` are schedulable on a single CPU.
+    fn accepts(&self, tasks: &[T]) -> bool;
+}
+
+/// Any function or closure on slices of tasks is a test.
+impl<T, F> UniTest<T> for F where F: Fn(&[T]) -> bool {
+    fn accepts(&self, tasks: &[T]) -> bool {
+        self(tasks)
+    }
+}
+
+/// A test deciding whether a set of tasks can share a cluster of CPUs, such as
+/// one of the global tests of [`bound`](`crate::bound`).
+pub trait ClusterTest<T> {
+    /// Tests whether `tasks` are schedulable on a cluster of `num_cpus` CPUs.
+    fn accepts(&self, tasks: &[T], num_cpus: usize) -> bool;
+}
+
+/// Any function or closure on slices of tasks and a number of CPUs is a test.
+impl<T, F> ClusterTest<T> for F where F: Fn(&[T], usize) -> bool {
+    fn accepts(&self, tasks: &[T], num_cpus: usize) -> bool {
+        self(tasks, num_cpus)
+    }
+}
+
+/// Uniprocessor EDF, tested using [`uni::edf`] without blocking.
+pub struct Edf;
+
+impl<T: AsTask> UniTest<T> for Edf {
+    fn accepts(&self, tasks: &[T]) -> bool {
+        uni::edf(tasks, None)
+    }
+}
+
+/// Uniprocessor fixed-priority scheduling, tested using [`uni::fp`] without blocking.
+pub struct Fp;
+
+impl<T: AsTask> UniTest<T> for Fp {
+    fn accepts(&self, tasks: &[T]) -> bool {
+        uni::fp(tasks, None).iter().all(Option::is_some)
+    }
+}
+
+/// Assignment of each task of a task-set to a cluster.
+///
+/// For partitioned scheduling each cluster is a single CPU, and cluster indices
+/// are CPU indices.
+pub struct Assignment {
+    clusters: Box<[usize]>,
+    sizes: Box<[usize]>
+}
+
+impl Assignment {
+    /// Returns the number of clusters tasks are assigned to.
+    pub fn num_clusters(&self) -> usize {
+        self.sizes.len()
+    }
+
+    /// Returns the number of CPUs in cluster `cluster`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cluster` does not exist.
+    pub fn cluster_cpus(&self, cluster: usize) -> usize {
+        self.sizes[cluster]
+    }
+
+    /// Returns the cluster the task at index `task` is assigned to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `task` is out of bounds for the task-set.
+    pub fn cluster(&self, task: usize) -> usize {
+        self.clusters[task]
+    }
+
+    /// Returns the indices of the tasks assigned to cluster `cluster`, in increasing order.
+    pub fn tasks_in(&self, cluster: usize) -> impl Iterator<Item = usize> + '_ {
+        self.clusters.iter()
+                     .enumerate()
+                     .filter(move |(_, c)| **c == cluster)
+                     .map(|(task, _)| task)
+    }
+}
+
+/// A piece of a task in a semi-partitioned assignment.
+#[derive(Clone, Copy)]
+pub struct Piece {
+    /// Index of the task the piece belongs to.
+    pub task: usize,
+    /// Release offset of the piece, relative to the release of each job of the task.
+    pub offset: Time,
+    /// The piece itself, as a task with the cost and relative deadline of the piece
+    /// and the period of the task.
+    pub sub: Task
+}
+
+/// Semi-partitioned assignment of tasks to CPUs, in which some tasks are split into
+/// pieces that execute on different CPUs.
+///
+/// Each job of a split task executes its pieces in order of offset, migrating from
+/// one CPU to the next; every other task is assigned whole to a single CPU, as a
+/// single piece with zero offset.
+pub struct Split {
+    cpus: Box<[Box<[Piece]>]>
+}
+
+impl Split {
+    /// Returns the number of CPUs tasks are assigned to.
+    pub fn num_cpus(&self) -> usize {
+        self.cpus.len()
+    }
+
+    /// Returns the pieces assigned to CPU `cpu`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cpu` does not exist.
+    pub fn pieces_on(&self, cpu: usize) -> &[Piece] {
+        &self.cpus[cpu]
+    }
+
+    /// Tests whether the task at index `task` has been split, i.e. if it migrates.
+    pub fn is_migrating(&self, task: usize) -> bool {
+        self.cpus.iter()
+                 .flat_map(|ps| ps.iter())
+                 .any(|p| p.task == task && p.offset > 0)
+    }
+
+    /// Returns the indices of the tasks that have been split, in increasing order.
+    pub fn migrating(&self) -> Box<[usize]> {
+        let mut out = self.cpus.iter()
+                               .flat_map(|ps| ps.iter())
+                               .filter(|p| p.offset > 0)
+                               .map(|p| p.task)
+                               .collect::<Vec<_>>();
+
+        out.sort_unstable();
+        out.dedup();
+        out.into_boxed_slice()
+    }
+}
+
+/// Returns the value used to sort `task` according to `order`.
+fn weight(task: &Task, order: Order) -> RBig {
+    let den = match order {
+        Order::Utilization => task.period,
+        Order::Density => task.deadline.min(task.period)
+    };
+
+    RBig::from(task.cost) / RBig::from(den)
+}
+
+/// Partitioner for task-sets.
+pub struct Partitioner {
+    num_cpus: usize,
+    fit: Fit,
+    order: Order
+}
+
+impl Partitioner {
+    /// Constructs a new `Partitioner` onto `num_cpus` CPUs with the given heuristics.
+    ///
+    /// The partitioner places tasks onto single CPUs, or onto clusters with
+    /// [`run_clustered`](`Self::run_clustered`).
+    pub fn new(num_cpus: usize, fit: Fit, order: Order) -> Self {
+        Self { num_cpus, fit, order }
+    }
+
+    /// Picks one of the clusters in `fits` according to the fit heuristic, given the
+    /// current utilization per CPU of every cluster.
+    fn pick(&self, loads: &[RBig], mut fits: impl Iterator<Item = usize>) -> Option<usize> {
+        match self.fit {
+            Fit::First => fits.next(),
+            Fit::Best  => fits.max_by(|a, b| loads[*a].cmp(&loads[*b]).then(b.cmp(a))),
+            Fit::Worst => fits.min_by(|a, b| loads[*a].cmp(&loads[*b]).then(a.cmp(b)))
+        }
+    }
+
+    /// Runs the partitioner on task-set `tasks`, validating every placement onto a
+    /// single CPU with the uniprocessor test `test`.
+    ///
+    /// See [`run_clustered`](`Self::run_clustered`) for the return value.
+    pub fn run<T: AsTask + Clone>(&self, tasks: &[T], test: impl UniTest<T>)
+    -> Result<Assignment, Box<[usize]>> {
+        self.run_clustered(tasks, 1, |bin: &[T], _| test.accepts(bin))
+    }
+
+    /// Runs the partitioner on task-set `tasks` onto clusters of `cluster_size` CPUs,
+    /// validating every placement onto a cluster with `test`.
+    ///
+    /// If `cluster_size` does not divide the number of CPUs, the last cluster
+    /// contains the remaining CPUs.
+    ///
+    /// Returns the assignment of every task if all tasks could be placed; otherwise,
+    /// returns the indices of the tasks that could not be placed in the order they
+    /// were tried. Tasks that do not fit do not stop the placement of the others.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cluster_size` is zero.
+    pub fn run_clustered<T: AsTask + Clone>(&self, tasks: &[T], cluster_size: usize, test: impl ClusterTest<T>)
+    -> Result<Assignment, Box<[usize]>> {
+        assert!(cluster_size > 0);
+
+        let plain = tasks.iter().map(AsTask::as_task).collect::<Box<_>>();
+
+        let mut order = (0 .. tasks.len()).collect::<Box<_>>();
+        order.sort_by_cached_key(|i| std::cmp::Reverse(weight(&plain[*i], self.order)));
+
+        let sizes = (0 .. self.num_cpus).step_by(cluster_size)
+                                        .map(|cpu| cluster_size.min(self.num_cpus - cpu))
+                                        .collect::<Box<_>>();
+
+        let mut bins = vec![Vec::new(); sizes.len()];
+        let mut loads = vec![RBig::ZERO; sizes.len()];
+        let mut clusters = vec![0; tasks.len()].into_boxed_slice();
+        let mut failed = Vec::new();
+
+        for task in order {
+            let util = RBig::from(plain[task].cost) / RBig::from(plain[task].period);
+
+            let fits = (0 .. sizes.len()).filter(|cl| {
+                let mut bin = bins[*cl].clone();
+                bin.push(tasks[task].clone());
+                test.accepts(&bin, sizes[*cl])
+            });
+
+            let Some(cluster) = self.pick(&loads, fits) else {
+                failed.push(task);
+                continue;
+            };
+
+            bins[cluster].push(tasks[task].clone());
+            loads[cluster] += util / RBig::from(sizes[cluster]);
+            clusters[task] = cluster;
+        }
+
+        if failed.is_empty() {
+            Ok(Assignment { clusters, sizes })
+        } else {
+            Err(failed.into_boxed_slice())
+        }
+    }
+
+    /// Runs the partitioner on task-set `tasks` using the C=D task-splitting scheme, as
+    /// described in Burns, Davis, Wang and Zhang's "Partitioned EDF scheduling for
+    /// multiprocessors using a C=D task splitting scheme" (Real-Time Systems, 2012).
+    ///
+    /// Tasks are first placed whole as in [`run`](`Self::run`). Each task that does not fit
+    /// is then split: a piece with equal cost and deadline, as large as possible, is placed
+    /// on the CPU that can accept the largest such piece, and the rest of the task is released
+    /// when the piece completes, with the remaining cost and deadline. This is repeated until
+    /// the rest of the task fits on a CPU that does not already host one of its pieces.
+    /// Each piece keeps the release jitter of the task, so the deadline of a piece exceeds
+    /// its cost by the jitter, and the rest of the task arrives after the cost of the piece.
+    /// Every placement is validated by running the uniprocessor test `test`, which must
+    /// support constrained deadlines, on the pieces assigned to the CPU. Pieces are plain
+    /// tasks, obtained from `tasks` through [`AsTask`].
+    ///
+    /// Returns the assignment of every task if all tasks could be placed; otherwise,
+    /// returns the indices of the tasks that could not be placed.
+    pub fn run_split<T: AsTask>(&self, tasks: &[T], test: impl UniTest<Task>) -> Result<Split, Box<[usize]>> {
+        let tasks = tasks.iter().map(AsTask::as_task).collect::<Box<_>>();
+
+        let accepts = |bin: &[Piece], sub: Task| {
+            let mut bin = bin.iter().map(|p| p.sub).collect::<Vec<_>>();
+            bin.push(sub);
+            test.accepts(&bin)
+        };
+
+        // largest piece with zero laxity that fits in bin, if any
+        let largest = |bin: &[Piece], rest: &Task| {
+            let (mut lo, mut hi) = (0, rest.cost.min(rest.deadline.saturating_sub(rest.jitter)));
+
+            while lo < hi {
+                let mid = lo + (hi - lo).div_ceil(2);
+
+                if accepts(bin, Task { cost: mid, deadline: mid + rest.jitter, ..*rest }) {
+                    lo = mid;
+                } else {
+                    hi = mid - 1;
+                }
+            }
+
+            (lo > 0).then_some(lo)
+        };
+
+        let util = |t: &Task| RBig::from(t.cost) / RBig::from(t.period);
+
+        let mut order = (0 .. tasks.len()).collect::<Box<_>>();
+        order.sort_by_cached_key(|i| std::cmp::Reverse(weight(&tasks[*i], self.order)));
+
+        let mut bins = vec![Vec::new(); self.num_cpus];
+        let mut loads = vec![RBig::ZERO; self.num_cpus];
+        let mut unplaced = Vec::new();
+
+        for task in order {
+            let fits = (0 .. self.num_cpus).filter(|cpu| accepts(&bins[*cpu], tasks[task]));
+
+            let Some(cpu) = self.pick(&loads, fits) else {
+                unplaced.push(task);
+                continue;
+            };
+
+            bins[cpu].push(Piece { task, offset: 0, sub: tasks[task] });
+            loads[cpu] += util(&tasks[task]);
+        }
+
+        let mut failed = Vec::new();
+
+        'task: for task in unplaced {
+            let mut rest = tasks[task];
+            let mut offset = 0;
+            let mut pieces = Vec::new();
+
+            loop {
+                let free = |cpu: &usize| pieces.iter().all(|(c, _)| c != cpu);
+
+                let fits = (0 .. self.num_cpus).filter(free)
+                                               .filter(|cpu| accepts(&bins[*cpu], rest));
+
+                if let Some(cpu) = self.pick(&loads, fits) {
+                    pieces.push((cpu, Piece { task, offset, sub: rest }));
+                    break;
+                }
+
+                let best = (0 .. self.num_cpus).filter(free)
+                                               .filter_map(|cpu| largest(&bins[cpu], &rest).map(|c| (c, cpu)))
+                                               .max_by(|(c1, cpu1), (c2, cpu2)| c1.cmp(c2).then(cpu2.cmp(cpu1)));
+
+                let Some((cost, cpu)) = best.filter(|(c, _)| *c < rest.cost) else {
+                    failed.push(task);
+                    continue 'task;
+                };
+
+                pieces.push((cpu, Piece { task, offset, sub: Task { cost, deadline: cost + rest.jitter, ..rest } }));
+
+                offset += cost;
+                rest = Task { cost: rest.cost - cost, deadline: rest.deadline - cost, ..rest };
+            }
+
+            for (cpu, piece) in pieces {
+                loads[cpu] += util(&piece.sub);
+                bins[cpu].push(piece);
+            }
+        }
+
+        if failed.is_empty() {
+            Ok(Split {
+                cpus: bins.into_iter().map(Vec::into_boxed_slice).collect()
+            })
+        } else {
+            Err(failed.into_boxed_slice())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sharing::{ObliviousData, ObliviousTask, Bound};
+
+    fn clusters(a: &Assignment, num_tasks: usize) -> Box<[usize]> {
+        (0 .. num_tasks).map(|i| a.cluster(i)).collect()
+    }
+
+    #[test]
+    fn fits() {
+        let ts = [Task::new(5, 10), Task::new(3, 10), Task::new(5, 10), Task::new(3, 10)];
+
+        let first = Partitioner::new(2, Fit::First, Order::Utilization).run(&ts, Edf).unwrap();
+        assert_eq!(*clusters(&first, 4), [0, 1, 0, 1]);
+
+        let worst = Partitioner::new(2, Fit::Worst, Order::Utilization).run(&ts, Edf).unwrap();
+        assert_eq!(*clusters(&worst, 4), [0, 0, 1, 1]);
+
+        let best = Partitioner::new(3, Fit::Best, Order::Utilization).run(&ts, Edf).unwrap();
+        assert_eq!(*clusters(&best, 4), [0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn unplaced() {
+        let ts = [Task::new(6, 10); 3];
+
+        assert_eq!(*Partitioner::new(2, Fit::First, Order::Utilization).run(&ts, Edf).err().unwrap(), [2]);
+        assert_eq!(*Partitioner::new(2, Fit::First, Order::Utilization).run(&ts, Fp).err().unwrap(), [2]);
+    }
+
+    #[test]
+    fn density_order() {
+        // by density the constrained task is placed first and gets its own CPU
+        let ts = [Task::new(4, 10), Task::new(3, 10).with_deadline(4)];
+
+        let a = Partitioner::new(2, Fit::Worst, Order::Density).run(&ts, Edf).unwrap();
+        assert_eq!(*clusters(&a, 2), [1, 0]);
+    }
+
+    #[test]
+    fn inflated() {
+        let ts = [Task::new(4, 10), Task::new(4, 10)];
+        let data = [ObliviousData::from(Bound::new(2)), ObliviousData::from(Bound::new(2))];
+        let inflated = ts.iter().zip(&data).map(ObliviousTask::from).collect::<Box<_>>();
+
+        assert_eq!(*clusters(&Partitioner::new(1, Fit::First, Order::Utilization).run(&ts, Fp).unwrap(), 2), [0, 0]);
+        assert!(Partitioner::new(1, Fit::First, Order::Utilization).run(&inflated, Fp).is_err());
+        assert!(Partitioner::new(2, Fit::First, Order::Utilization).run(&inflated, Fp).is_ok());
+    }
+
+    #[test]
+    fn clustered() {
+        use crate::task::Set;
+        use num_order::NumOrd;
+
+        let ts = [Task::new(9, 10), Task::new(9, 10), Task::new(9, 10), Task::new(9, 10), Task::new(9, 10)];
+        let test = |bin: &[Task], m: usize| bin.iter().utilization().num_le(&m);
+
+        // five CPUs in clusters of two, the last with a single CPU
+        let a = Partitioner::new(5, Fit::First, Order::Utilization).run_clustered(&ts, 2, test).unwrap();
+
+        assert_eq!(a.num_clusters(), 3);
+        assert_eq!((a.cluster_cpus(0), a.cluster_cpus(2)), (2, 1));
+        assert_eq!(*clusters(&a, 5), [0, 0, 1, 1, 2]);
+        assert_eq!(a.tasks_in(1).collect::<Vec<_>>(), [2, 3]);
+
+        let b = Partitioner::new(4, Fit::First, Order::Utilization).run_clustered(&ts, 4, test);
+        assert_eq!(*b.err().unwrap(), [4]);
+    }
+
+    #[test]
+    fn split() {
+        // the third task is split into a piece of cost 4 and deadline 4 on the first CPU,
+        // followed by the rest of cost 2 and deadline 6 on the second CPU
+        let ts = [Task::new(6, 10); 3];
+        let s = Partitioner::new(2, Fit::First, Order::Utilization).run_split(&ts, Edf).unwrap();
+
+        assert_eq!(s.num_cpus(), 2);
+        assert_eq!(*s.migrating(), [2]);
+        assert!(s.is_migrating(2) && !s.is_migrating(0));
+
+        let pieces = |cpu| s.pieces_on(cpu).iter().map(|p| (p.task, p.offset, p.sub.cost, p.sub.deadline)).collect::<Vec<_>>();
+
+        assert_eq!(pieces(0), [(0, 0, 6, 10), (2, 0, 4, 4)]);
+        assert_eq!(pieces(1), [(1, 0, 6, 10), (2, 4, 2, 6)]);
+    }
+
+    #[test]
+    fn split_failure() {
+        let ts = [Task::new(9, 10); 3];
+
+        assert_eq!(*Partitioner::new(2, Fit::First, Order::Utilization).run_split(&ts, Edf).err().unwrap(), [2]);
+        assert!(Partitioner::new(2, Fit::First, Order::Utilization).run_split(&ts[.. 2], Edf).unwrap().migrating().is_empty());
+    }
+
+    #[test]
+    fn split_inflated() {
+        let ts = [Task::new(4, 10); 3];
+        let data = [ObliviousData::from(Bound::new(2)); 3];
+        let inflated = ts.iter().zip(&data).map(ObliviousTask::from).collect::<Box<_>>();
+
+        assert!(Partitioner::new(2, Fit::First, Order::Utilization).run_split(&ts, Edf).unwrap().migrating().is_empty());
+
+        let s = Partitioner::new(2, Fit::First, Order::Utilization).run_split(&inflated, Edf).unwrap();
+        assert_eq!(*s.migrating(), [2]);
+    }
+}
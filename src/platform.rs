@@ -0,0 +1,238 @@
+//! Uniform multiprocessor platforms.
+//!
+//! On a uniform multiprocessor each CPU has a _speed_, i.e. the amount of work it
+//! completes per time unit. Task costs and request lengths are measured as work, i.e.
+//! as the time taken to complete them on a CPU of speed `1`.
+
+use crate::{
+    task::{Task, AsTask, Time},
+    rsrc::{System, RequestKind, Request}
+};
+
+use dashu::rational::RBig;
+
+/// A uniform multiprocessor platform.
+#[derive(Clone)]
+pub struct Platform {
+    speeds: Box<[RBig]>
+}
+
+impl Platform {
+    /// Constructs a new `Platform` with one CPU for each speed in `speeds`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `speeds` is empty or contains a non-positive speed.
+    pub fn new(speeds: impl IntoIterator<Item = RBig>) -> Self {
+        let mut speeds = speeds.into_iter().collect::<Box<_>>();
+
+        assert!(!speeds.is_empty());
+        assert!(speeds.iter().all(|s| *s > RBig::ZERO));
+
+        speeds.sort_unstable_by(|a, b| b.cmp(a));
+        Self { speeds }
+    }
+
+    /// Constructs a new `Platform` with `num_cpus` identical CPUs of speed `1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_cpus` is zero.
+    pub fn identical(num_cpus: usize) -> Self {
+        Self::new(vec![RBig::ONE; num_cpus])
+    }
+
+    /// Returns the number of CPUs of the platform.
+    pub fn num_cpus(&self) -> usize {
+        self.speeds.len()
+    }
+
+    /// Returns the speeds of the CPUs of the platform, in decreasing order.
+    pub fn speeds(&self) -> &[RBig] {
+        &self.speeds
+    }
+
+    /// Returns the speed of the slowest CPU of the platform.
+    pub fn slowest(&self) -> &RBig {
+        self.speeds.last().unwrap()
+    }
+
+    /// Returns the total capacity of the platform, i.e. the sum of its speeds.
+    pub fn capacity(&self) -> RBig {
+        self.speeds.iter().fold(RBig::ZERO, |acc, s| acc + s)
+    }
+
+    /// Returns the parameter `λ` of the platform, i.e. the largest ratio between the
+    /// total speed of the CPUs slower than a given CPU and the speed of the latter.
+    pub fn lambda(&self) -> RBig {
+        (0 .. self.speeds.len()).map(|j| {
+            self.speeds[j + 1 ..].iter().fold(RBig::ZERO, |acc, s| acc + s) / &self.speeds[j]
+        }).max().unwrap_or_default()
+    }
+
+    /// Returns the parameter `μ` of the platform, i.e. the largest ratio between the
+    /// total speed of the CPUs no faster than a given CPU and the speed of the latter,
+    /// which equals `λ + 1`.
+    pub fn mu(&self) -> RBig {
+        self.lambda() + RBig::ONE
+    }
+
+    /// Returns the time taken to complete `work` on CPU `cpu`, rounded up; CPUs are
+    /// indexed in decreasing order of speed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cpu` does not exist.
+    pub fn duration(&self, work: Time, cpu: usize) -> Time {
+        let time = RBig::from(work) / &self.speeds[cpu];
+        Time::try_from(time.ceil()).unwrap()
+    }
+
+    /// Returns system `sys` with the length of each request replaced by the time taken
+    /// to complete it on the CPU it executes on, so that locking analyzers run on the
+    /// result give bounds in units of time.
+    ///
+    /// If given, `cpus` contains the CPU each task is assigned to, as in a partitioned
+    /// system; otherwise, tasks may execute on any CPU, and the slowest one is assumed.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `cpus` is given and its length differs from the number of tasks of `sys`,
+    /// or if it contains a non-existent CPU.
+    pub fn scale_requests<'a, K>(&self, sys: &System<'a, K>, cpus: Option<&[usize]>) -> System<'a, K>
+    where K: RequestKind {
+        if let Some(cpus) = cpus {
+            assert_eq!(cpus.len(), sys.num_tasks());
+        }
+
+        sys.map_requests(|task, req| {
+            let cpu = cpus.map_or(self.num_cpus() - 1, |c| c[task]);
+            Request { length: self.duration(req.length, cpu), ..req }
+        })
+    }
+}
+
+/// Returns the total and largest utilization of `ts`, if implicit.
+fn utils(ts: impl IntoIterator<Item: AsTask>) -> Option<(RBig, RBig)> {
+    let ts = ts.into_iter().map(|t| t.as_task()).collect::<Box<[Task]>>();
+
+    if ts.iter().any(|t| t.deadline != t.period || t.jitter > 0) {
+        return None;
+    }
+
+    let utils = ts.iter().map(|t| RBig::from(t.cost) / RBig::from(t.period));
+
+    Some(utils.fold((RBig::ZERO, RBig::ZERO), |(sum, max), u| (sum + &u, max.max(u))))
+}
+
+/// Tests whether task-set `ts` is feasible on `platform`, using the exact condition
+/// for implicit-deadline tasks given by Funk, Goossens and Baruah in "On-line scheduling
+/// on uniform multiprocessors" (RTSS, 2001): for each `k`, the `k` largest utilizations
+/// must not exceed the `k` largest speeds, and the total utilization must not exceed the
+/// capacity of the platform.
+///
+/// Returns the result of the test if `ts` is implicit and has no release jitter,
+/// otherwise `None`.
+pub fn feasible(ts: impl IntoIterator<Item: AsTask>, platform: &Platform) -> Option<bool> {
+    let ts = ts.into_iter().map(|t| t.as_task()).collect::<Box<[Task]>>();
+    let (total, _) = utils(ts.iter())?;
+
+    let mut utils = ts.iter()
+                      .map(|t| RBig::from(t.cost) / RBig::from(t.period))
+                      .collect::<Box<_>>();
+    utils.sort_unstable_by(|a, b| b.cmp(a));
+
+    let (mut u, mut s) = (RBig::ZERO, RBig::ZERO);
+
+    let prefix = utils.iter().zip(platform.speeds()).all(|(ui, si)| {
+        u += ui;
+        s += si;
+        u <= s
+    });
+
+    Some(prefix && total <= platform.capacity())
+}
+
+/// Funk, Goossens and Baruah's test for global EDF on uniform multiprocessors, as
+/// described in "On-line scheduling on uniform multiprocessors" (RTSS, 2001).
+///
+/// The task-set is deemed schedulable if the capacity of the platform is at least
+/// `U + λ u_max`, where `U` is the total utilization, `u_max` the largest utilization
+/// and `λ` is given by [`Platform::lambda`].
+///
+/// Returns the result of the test if `ts` is implicit and has no release jitter,
+/// otherwise `None`.
+pub fn gedf(ts: impl IntoIterator<Item: AsTask>, platform: &Platform) -> Option<bool> {
+    let (total, max) = utils(ts)?;
+    Some(platform.capacity() >= total + platform.lambda() * max)
+}
+
+/// Baruah and Goossens' test for global rate-monotonic scheduling on uniform
+/// multiprocessors, as described in "Rate-monotonic scheduling on uniform
+/// multiprocessors" (IEEE TC, 2003).
+///
+/// The task-set is deemed schedulable if the capacity of the platform is at least
+/// `2U + μ u_max`, with the same notation as [`gedf`] and `μ` given by [`Platform::mu`].
+/// On `m` identical CPUs, this accepts total utilizations up to `m / 3` when no task
+/// has utilization greater than `1 / 3`.
+///
+/// Returns the result of the test if `ts` is implicit and has no release jitter,
+/// otherwise `None`.
+pub fn grm(ts: impl IntoIterator<Item: AsTask>, platform: &Platform) -> Option<bool> {
+    let (total, max) = utils(ts)?;
+    Some(platform.capacity() >= RBig::from(2) * total + platform.mu() * max)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn uniform(speeds: &[u64]) -> Platform {
+        Platform::new(speeds.iter().map(|s| RBig::from(*s)))
+    }
+
+    #[test]
+    fn parameters() {
+        let p = uniform(&[1, 4, 2]);
+
+        assert_eq!(p.speeds(), [RBig::from(4), RBig::from(2), RBig::ONE]);
+        assert_eq!((p.capacity(), p.slowest().clone()), (RBig::from(7), RBig::ONE));
+        assert_eq!(p.lambda(), RBig::from(3) / RBig::from(4));
+        assert_eq!(p.mu(), RBig::from(7) / RBig::from(4));
+
+        assert_eq!(Platform::identical(3).lambda(), RBig::from(2));
+        assert_eq!(Platform::identical(3).mu(), RBig::from(3));
+        assert_eq!((p.duration(5, 0), p.duration(5, 2)), (2, 5));
+    }
+
+    #[test]
+    fn feasibility() {
+        let p = uniform(&[2, 1]);
+
+        // a task of utilization 2 only fits on the fastest CPU
+        assert_eq!(feasible([Task::new(4, 2), Task::new(1, 1)], &p), Some(true));
+        assert_eq!(feasible([Task::new(4, 2), Task::new(3, 2)], &p), Some(false));
+        assert_eq!(feasible([Task::new(5, 2)], &p), Some(false));
+        assert_eq!(feasible([Task::new(1, 2).with_deadline(1)], &p), None);
+    }
+
+    #[test]
+    fn gedf_identical() {
+        // on identical CPUs the test reduces to U + (m - 1) u_max <= m
+        let p = Platform::identical(2);
+
+        assert_eq!(gedf([Task::new(1, 2); 3], &p), Some(true));
+        assert_eq!(gedf([Task::new(1, 2), Task::new(1, 2), Task::new(1, 2), Task::new(1, 10)], &p), Some(false));
+    }
+
+    #[test]
+    fn grm_identical() {
+        let p = Platform::identical(3);
+
+        // tasks of utilization at most 1/3 are accepted up to a total of m / 3 = 1, while
+        // using λ instead of μ would wrongly accept up to (2m + 1) / 6 = 7/6
+        assert_eq!(grm([Task::new(1, 3); 3], &p), Some(true));
+        assert_eq!(grm([Task::new(1, 3), Task::new(1, 3), Task::new(1, 3), Task::new(1, 6)], &p), Some(false));
+        assert_eq!(grm([Task::new(1, 3); 3], &Platform::identical(2)), Some(false));
+    }
+}
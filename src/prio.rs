@@ -0,0 +1,262 @@
+//! Priority assignment for fixed-priority scheduling.
+//!
+//! All functions in this module fill [`Task::priority`] in place, using distinct
+//! priorities where lower values mean higher priority, as expected by the
+//! fixed-priority tests in [`bound`](`crate::bound`), except for [`thresholds`], which
+//! fills [`Task::threshold`] given the priorities. Since thresholds are expressed as
+//! priorities, assigning priorities also removes any preemption threshold.
+
+use crate::{
+    task::{Task, Time},
+    bound::pt,
+    rsrc::{System, RequestKind, Request, Usage, RequestSet},
+    sharing::{ObliviousAnalyzer, ObliviousData}
+};
+
+/// Assigns distinct priorities to `ts` in increasing order of `key`, breaking ties
+/// by index.
+fn by_key<K: Ord>(ts: &mut [Task], key: impl Fn(&Task) -> K) {
+    let mut order = (0 .. ts.len()).collect::<Box<_>>();
+    order.sort_by_key(|i| (key(&ts[*i]), *i));
+
+    for (prio, i) in order.iter().enumerate() {
+        ts[*i].priority = prio as u64;
+        ts[*i].threshold = u64::MAX;
+    }
+}
+
+/// Assigns rate-monotonic priorities to `ts`, i.e. higher priority to tasks with
+/// shorter periods.
+pub fn rm(ts: &mut [Task]) {
+    by_key(ts, |t| t.period);
+}
+
+/// Assigns deadline-monotonic priorities to `ts`, i.e. higher priority to tasks with
+/// shorter relative deadlines.
+pub fn dm(ts: &mut [Task]) {
+    by_key(ts, |t| t.deadline);
+}
+
+/// Assigns priorities to `ts` using Audsley's _Optimal Priority Assignment_ (OPA),
+/// as described in "On priority assignment in fixed priority scheduling" (Information
+/// Processing Letters, 2001).
+///
+/// Priority levels are assigned from the lowest to the highest: at each level, the
+/// first task for which `test` succeeds is assigned that level. `test` is given the
+/// task-set and the index of the task under analysis, which has the lowest priority
+/// among the tasks not yet assigned a lower level; all such tasks share the highest
+/// priority. The assignment is optimal with respect to `test` if the latter only
+/// depends on the set of tasks with higher priority and not on their relative order,
+/// which holds for example for [`uni::fp`](`crate::bound::uni::fp`).
+///
+/// Returns whether every task could be assigned a priority; if not, the priorities
+/// of `ts` are only partially assigned.
+pub fn audsley(ts: &mut [Task], test: impl Fn(&[Task], usize) -> bool) -> bool {
+    let mut assigned = vec![false; ts.len()];
+
+    for task in ts.iter_mut() {
+        task.priority = 0;
+        task.threshold = u64::MAX;
+    }
+
+    for level in (1 ..= ts.len() as u64).rev() {
+        let mut found = false;
+
+        for k in 0 .. ts.len() {
+            if assigned[k] {
+                continue;
+            }
+
+            ts[k].priority = level;
+
+            if test(ts, k) {
+                assigned[k] = true;
+                found = true;
+                break;
+            }
+
+            ts[k].priority = 0;
+        }
+
+        if !found {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Assigns priorities to `ts` using Audsley's OPA as in [`audsley`], re-running the
+/// locking analyzer `an` on the requests of `sys` whenever priorities change.
+///
+/// `test` is additionally given the blocking bounds of each task computed by `an`
+/// with the current priorities. Since blocking bounds may depend on the relative
+/// priorities of other tasks, the resulting assignment is not guaranteed to be
+/// optimal; for the same reason, the final assignment is validated by re-running
+/// `an` and `test` on every task.
+///
+/// Returns whether every task could be assigned a priority and the final assignment
+/// passes the validation.
+///
+/// # Panics
+///
+/// Panics if `ts` and the task-set of `sys` have different sizes.
+pub fn audsley_blocking<K, A>(
+    ts: &mut [Task],
+    sys: &System<'_, K>,
+    an: &A,
+    test: impl Fn(&[Task], &[ObliviousData], usize) -> bool
+) -> bool where K: RequestKind,
+                K::Base<Request>: Clone,
+                A: ObliviousAnalyzer<K> + ?Sized,
+                for<'x> Usage<'x, K>: RequestSet {
+    let blocking = |ts: &[Task]| sys.with_tasks(ts).run::<A>(an);
+
+    audsley(ts, |ts, k| test(ts, &blocking(ts), k)) && {
+        let data = blocking(ts);
+        (0 .. ts.len()).all(|k| test(ts, &data, k))
+    }
+}
+
+/// Assigns preemption thresholds to `ts` given its priorities, using Wang and Saksena's
+/// algorithm from "Scheduling fixed-priority tasks with preemption threshold" (RTCSA,
+/// 1999), with the analysis of [`pt::fp`] and additional `blocking` as for it.
+///
+/// Tasks are considered from the lowest priority to the highest: each is given the
+/// lowest threshold, among its priority, those of higher-priority tasks and the one
+/// just above the highest priority, for which it meets its deadline; any other
+/// threshold allows the same preemptions as one of these. Since the response time of a task only depends on its own
+/// threshold and on those of lower-priority tasks, and raising a threshold only
+/// increases the blocking induced on higher-priority tasks, the assignment succeeds
+/// whenever some assignment of thresholds makes `ts` schedulable with the given
+/// priorities according to [`pt::fp`]. This optimality is only with respect to that
+/// analysis and to `blocking` as given: if `blocking` comes from a locking analyzer,
+/// it may itself depend on the thresholds, and should be recomputed and checked with
+/// [`pt::fp`] after the assignment.
+///
+/// Returns whether every task could be assigned a threshold; if not, `ts` is left
+/// unchanged.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn thresholds(ts: &mut [Task], blocking: Option<&[Time]>) -> bool {
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), ts.len());
+    }
+
+    let mut order = (0 .. ts.len()).collect::<Box<_>>();
+    order.sort_by_key(|i| std::cmp::Reverse(ts[*i].priority));
+
+    let mut out = ts.iter()
+                    .map(|t| Task { threshold: t.priority, ..*t })
+                    .collect::<Box<_>>();
+
+    for k in order {
+        let mut levels = out.iter()
+                            .map(|t| t.priority)
+                            .filter(|p| *p <= out[k].priority)
+                            .collect::<Vec<_>>();
+
+        levels.sort_unstable_by(|a, b| b.cmp(a));
+        levels.dedup();
+        // not preemptable by any task
+        levels.extend(levels.last().and_then(|p| p.checked_sub(1)));
+
+        let found = levels.into_iter().any(|level| {
+            out[k].threshold = level;
+
+            // blocking from lower-priority tasks, whose thresholds are already assigned
+            let block = pt::blocking(&out)[k].max(blocking.map_or(0, |b| b[k]));
+            pt::response(&out, k, block).is_some()
+        });
+
+        if !found {
+            return false;
+        }
+    }
+
+    ts.copy_from_slice(&out);
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::bound::uni;
+
+    fn priorities(ts: &[Task]) -> Vec<u64> {
+        ts.iter().map(|t| t.priority).collect()
+    }
+
+    fn opa(ts: &mut [Task]) -> bool {
+        audsley(ts, |ts, k| uni::fp(ts.iter(), None)[k].is_some())
+    }
+
+    #[test]
+    fn monotonic() {
+        let mut ts = [Task::new(1, 10).with_deadline(4), Task::new(1, 5), Task::new(1, 10)];
+
+        rm(&mut ts);
+        assert_eq!(priorities(&ts), [1, 0, 2]);
+
+        dm(&mut ts);
+        assert_eq!(priorities(&ts), [0, 1, 2]);
+    }
+
+    #[test]
+    fn audsley_buttazzo() {
+        let mut ts = [Task::new(3, 10), Task::new(1, 4), Task::new(2, 6)];
+
+        assert!(opa(&mut ts));
+        assert!(uni::fp(ts, None).iter().all(Option::is_some));
+
+        // with one more unit of cost, no priority ordering is feasible
+        let mut ts = [Task::new(4, 10), Task::new(1, 4), Task::new(2, 6)];
+        assert!(!opa(&mut ts));
+    }
+
+    #[test]
+    fn audsley_beats_dm() {
+        // deadline-monotonic priorities are not optimal for arbitrary deadlines
+        let mut ts = [Task::new(1, 2).with_deadline(4), Task::new(3, 6).with_deadline(5)];
+
+        dm(&mut ts);
+        assert_eq!(uni::fp(ts, None)[1], None);
+
+        assert!(opa(&mut ts));
+        assert_eq!(uni::fp(ts, None).as_ref(), [Some(4), Some(3)]);
+    }
+
+    #[test]
+    fn wang_saksena() {
+        let ts = [
+            Task { priority: 1, ..Task::new(20, 70).with_deadline(50) },
+            Task { priority: 2, ..Task::new(20, 80) },
+            Task { priority: 3, ..Task::new(35, 200).with_deadline(100) }
+        ];
+
+        let mut out = ts;
+        assert!(thresholds(&mut out, None));
+        assert_eq!(out.map(|t| t.threshold), [1, 0, 1]);
+        assert!(pt::fp(out, None).iter().all(Option::is_some));
+
+        // reassigning priorities removes thresholds
+        dm(&mut out);
+        assert!(out.iter().all(|t| t.threshold == u64::MAX));
+    }
+
+    #[test]
+    fn thresholds_failure() {
+        let mut out = [
+            Task { priority: 1, ..Task::new(20, 70).with_deadline(50) },
+            Task { priority: 2, ..Task::new(20, 80) },
+            Task { priority: 3, ..Task::new(35, 200).with_deadline(100) }
+        ];
+
+        // the lowest-priority task cannot absorb any blocking with any threshold
+        assert!(!thresholds(&mut out, Some(&[0, 0, 10])));
+        assert!(out.iter().all(|t| t.threshold == u64::MAX));
+    }
+}
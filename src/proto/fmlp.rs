@@ -8,19 +8,28 @@ pub struct FlexibleMulti;
 
 /// Implements the analysis for FMLP as described in [10.1109/RTCSA.2007.8](https://doi.org/10.1109/RTCSA.2007.8),
 /// with short requests only.
+///
+/// Short requests are spin-based and executed non-preemptively; the arrival bound of
+/// each task is its longest non-preemptive region, i.e. the longest time spent spinning
+/// for and then executing a single request.
 impl ObliviousAnalyzer<Mutex> for FlexibleMulti {
     fn pass(&self, task: usize, sys: &System<Mutex>, by_rsrc: &[Usage<Mutex>]) -> ObliviousData {
-        sys.reqs_by(task).iter().zip(by_rsrc)
-           .map(|(req, rset)| {
+        let mut out = ObliviousData::default();
+
+        for (req, rset) in sys.reqs_by(task).iter().zip(by_rsrc) {
             if req.num == 0 {
-                return Bound::default();
+                continue;
             }
 
             // every other task may block once per request
             let limits = Limits { total: sys.num_tasks() - 1, per_task: 1 };
+            let spin = rset.iter().copied().bound_blocking(task, limits);
+
+            out.total += req.num * spin;
+            out.arrival = out.arrival.max(spin + Bound::new(req.length));
+        }
 
-            rset.iter().copied().bound_blocking(task, limits * req.num)
-        }).sum::<Bound>().into()
+        out
     }
 
     fn name(&self) -> &'static str { "FMLP" }
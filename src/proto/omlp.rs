@@ -1,4 +1,5 @@
 use crate::{
+    task::Policy,
     rsrc::{System, Mutex, Rw, RwPair, Usage},
     sharing::{ObliviousAnalyzer, Bound, BoundBlocking, Limits, ObliviousData}
 };
@@ -45,13 +46,21 @@ impl ObliviousAnalyzer<Mutex> for GlobalOm {
 /// The clustered version of Brandenburg and Anderson's _O(m) Locking Protocol_ family
 /// (C-OMLP), specialized for the single-cluster case.
 pub struct SingleClusterOm {
-    num_cpus: usize
+    num_cpus: usize,
+    policy: Policy
 }
         
 impl SingleClusterOm {
-    /// Constructs a new instance of `SingleClusterOm` with the given number of CPUs.
+    /// Constructs a new instance of `SingleClusterOm` with the given number of CPUs,
+    /// under fixed-priority scheduling.
     pub fn new(num_cpus: usize) -> Self {
-        Self { num_cpus }
+        Self { num_cpus, policy: Policy::Fixed }
+    }
+
+    /// Returns the analyzer for tasks scheduled under `policy`, which determines the
+    /// tasks that may be forced to donate their priority.
+    pub fn with_policy(self, policy: Policy) -> Self {
+        Self { policy, ..self }
     }
 }
 
@@ -92,7 +101,7 @@ impl ObliviousAnalyzer<Mutex> for SingleClusterOm {
         for task in 0 .. sys.num_tasks() {
             out[task].total += (0 .. sys.num_tasks())
                                .filter(|i| *i != task
-                                        && sys.task(*i).priority <= sys.task(task).priority)
+                                        && self.policy.dominates(sys.task(*i), sys.task(task)))
                                .map(|i| out[i].arrival)
                                .max()
                                .unwrap_or_default();
@@ -182,7 +191,7 @@ impl ObliviousAnalyzer<Rw> for SingleClusterOm {
         for task in 0 .. sys.num_tasks() {
             out[task].total += (0 .. sys.num_tasks())
                                .filter(|i| *i != task
-                                        && sys.task(*i).priority <= sys.task(task).priority)
+                                        && self.policy.dominates(sys.task(*i), sys.task(task)))
                                .map(|i| out[i].arrival)
                                .max()
                                .unwrap_or_default();
@@ -191,3 +200,59 @@ impl ObliviousAnalyzer<Rw> for SingleClusterOm {
 
     fn name(&self) -> &'static str { "CRW-OMLP" }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{rsrc::Request, task::{Task, Time}};
+
+    /// Three tasks on two CPUs, each making one request to a single resource; the task
+    /// with the highest fixed priority has the latest deadline.
+    fn system(tasks: &[Task]) -> System<'_, Mutex> {
+        let mut sys = System::new(tasks);
+        let rsrc = sys.add_rsrc();
+
+        for (task, length) in [1, 2, 4].into_iter().enumerate() {
+            sys.add_req(task, rsrc, Request { num: 1, length });
+        }
+
+        sys
+    }
+
+    fn tasks() -> [Task; 3] {
+        [Task { priority: 0, ..Task::new(1, 100) },
+         Task { priority: 1, ..Task::new(1, 10) },
+         Task { priority: 2, ..Task::new(1, 50) }]
+    }
+
+    fn totals(out: &[ObliviousData]) -> Vec<Time> {
+        out.iter().map(|d| d.total.length).collect()
+    }
+
+    #[test]
+    fn donation_fixed() {
+        let ts = tasks();
+        let out = system(&ts).run::<SingleClusterOm>(SingleClusterOm::new(2));
+
+        // one request of each other task, plus the longest arrival blocking (own
+        // request included) of any task with a higher or equal priority
+        assert_eq!(totals(&out), [4, 4 + 5, 2 + 6]);
+    }
+
+    #[test]
+    fn donation_edf() {
+        let ts = tasks();
+        let out = system(&ts).run::<SingleClusterOm>(SingleClusterOm::new(2).with_policy(Policy::Edf));
+
+        assert_eq!(totals(&out), [4 + 6, 4, 2 + 6]);
+    }
+
+    #[test]
+    fn donation_fifo() {
+        let ts = tasks();
+        let out = system(&ts).run::<SingleClusterOm>(SingleClusterOm::new(2).with_policy(Policy::Fifo));
+
+        assert_eq!(totals(&out), [4 + 6, 4 + 6, 2 + 6]);
+    }
+}
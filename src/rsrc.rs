@@ -101,6 +101,13 @@ pub trait RequestKind {
     /// in output --- in however way that elements of [`Base`](`Self::Base`) are defined.
     fn transpose<'a>(it: impl Iterator<Item = &'a Stored<Self>> + Clone) -> Usage<'a, Self>
         where Stored<Self>: 'a;
+
+    /// Applies `f` to each set of requests in `base`, maintaining its structure.
+    fn map(base: &Stored<Self>, f: impl Fn(Request) -> Request) -> Stored<Self>;
+
+    /// Returns the total length of the requests in `base`, i.e. the sum over each set
+    /// of requests of their number times their maximum length.
+    fn total(base: &Stored<Self>) -> Time;
 }
 
 /// Marker struct for mutex-type requests.
@@ -124,6 +131,14 @@ macro_rules! group_by_rsrc_impl {
             fn transpose<'a>(it: impl Iterator<Item = &'a Stored<Self>> + Clone) -> Usage<'a, Self> {
                 collect_by_rsrc!(it)
             }
+
+            fn map(base: &Stored<Self>, f: impl Fn(Request) -> Request) -> Stored<Self> {
+                f(*base)
+            }
+
+            fn total(base: &Stored<Self>) -> Time {
+                base.num as Time * base.length
+            }
         }
     };
 
@@ -134,6 +149,14 @@ macro_rules! group_by_rsrc_impl {
             fn transpose<'a>(it: impl Iterator<Item = &'a Stored<Self>> + Clone) -> Usage<'a, Self> {
                 $cont(collect_by_rsrc!(it, 0))
             }
+
+            fn map(base: &Stored<Self>, f: impl Fn(Request) -> Request) -> Stored<Self> {
+                $cont(f(base.0))
+            }
+
+            fn total(base: &Stored<Self>) -> Time {
+                base.0.num as Time * base.0.length
+            }
         }
 
         impl<T: RequestSet> RequestSet for $cont<T> {
@@ -150,6 +173,14 @@ macro_rules! group_by_rsrc_impl {
             fn transpose<'a>(it: impl Iterator<Item = &'a Stored<Self>> + Clone) -> Usage<'a, Self> {
                 $cont { $($field: collect_by_rsrc!(it.clone(), $field)),* }
             }
+
+            fn map(base: &Stored<Self>, f: impl Fn(Request) -> Request) -> Stored<Self> {
+                $cont { $($field: f(base.$field)),* }
+            }
+
+            fn total(base: &Stored<Self>) -> Time {
+                0 $(+ base.$field.num as Time * base.$field.length)*
+            }
         }
 
         impl<T: RequestSet> RequestSet for $cont<T> {
@@ -194,6 +225,19 @@ impl<'a, K> System<'a, K> where K: RequestKind {
     pub fn task(&self, i: usize) -> &'a Task {
         &self.tasks[i]
     }
+
+    /// Constructs a new `System` with the same task-set and resources as this one, and
+    /// with each set of requests replaced by the result of `f`, which is given the
+    /// index of the task making the requests and the original set.
+    pub fn map_requests(&self, f: impl Fn(usize, Request) -> Request) -> System<'a, K> {
+        System {
+            tasks: self.tasks,
+            num_rsrc: self.num_rsrc,
+            reqs: self.reqs.iter().enumerate().map(|(task, rs)| {
+                rs.iter().map(|r| K::map(r, |req| f(task, req))).collect()
+            }).collect()
+        }
+    }
 }
 
 impl<K> System<'_, K> where K: RequestKind {
@@ -202,6 +246,38 @@ impl<K> System<'_, K> where K: RequestKind {
         self.tasks.len()
     }
 
+    /// Returns the task-set of the system.
+    pub fn tasks(&self) -> &[Task] {
+        self.tasks
+    }
+
+    /// Constructs a new `System` with the same resources and requests as this one,
+    /// but with task-set `tasks`, for example to re-run analyzers after changing the
+    /// priorities of the tasks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `tasks` and the task-set of the system have different sizes.
+    pub fn with_tasks<'b>(&self, tasks: &'b [Task]) -> System<'b, K> where Stored<K>: Clone {
+        assert_eq!(tasks.len(), self.tasks.len());
+
+        System {
+            tasks,
+            num_rsrc: self.num_rsrc,
+            reqs: self.reqs.clone()
+        }
+    }
+
+    /// Returns the total length of the requests made by task `i`, i.e. the time it
+    /// spends in critical sections.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is out of bounds for the task-set.
+    pub fn request_time(&self, i: usize) -> Time {
+        self.reqs[i].iter().map(K::total).sum()
+    }
+
     /// Retrieves the list of requests made by task `i`.
     ///
     /// # Panics
@@ -235,7 +311,7 @@ impl<K> System<'_, K> where K: RequestKind {
     ///
     /// This differs from [`System::reqs_by`] in that the latter returns uses
     /// of each resource _from a given task_ as opposed to globally.
-    pub fn by_rsrc(&self) -> Box<[Usage<K>]> {
+    pub fn by_rsrc(&self) -> Box<[Usage<'_, K>]> {
         (0 .. self.num_rsrc).map(
             |rsrc| K::transpose(self.reqs.iter()
                                     .map(|rs| &rs[rsrc]))
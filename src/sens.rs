@@ -0,0 +1,237 @@
+//! Sensitivity analysis.
+//!
+//! Rather than whether a system is schedulable, the functions in this module compute
+//! by how much its parameters can change while it remains schedulable according to a
+//! given test, which is usually a closure running an analyzer and then a test from
+//! [`bound`](`crate::bound`). Tests are assumed to be _sustainable_, i.e. to keep
+//! accepting a system whose costs or requests are shortened.
+//!
+//! Factors are found by binary search over exact rationals, up to a given `precision`;
+//! the result is always a factor at which the system was accepted. Scaled times are
+//! rounded up, so that they are never shorter than the exact product.
+
+use crate::{
+    task::{Task, Time},
+    rsrc::{System, RequestKind, Request}
+};
+
+use dashu::rational::RBig;
+
+/// Returns `time` scaled by `factor`, rounded up.
+fn scale(time: Time, factor: &RBig) -> Time {
+    Time::try_from((RBig::from(time) * factor).ceil()).unwrap_or(Time::MAX)
+}
+
+/// Returns the largest factor in `[0, hi]` accepted by `accepts`, up to `precision`,
+/// or `None` if not even `0` is accepted.
+fn largest(hi: RBig, precision: &RBig, accepts: impl Fn(&RBig) -> bool) -> Option<RBig> {
+    assert!(*precision > RBig::ZERO);
+
+    if accepts(&hi) {
+        return Some(hi);
+    } else if !accepts(&RBig::ZERO) {
+        return None;
+    }
+
+    let (mut lo, mut hi) = (RBig::ZERO, hi);
+
+    while &hi - &lo > *precision {
+        let mid = (&lo + &hi) / RBig::from(2);
+
+        if accepts(&mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+
+    Some(lo)
+}
+
+/// Returns the largest factor by which the cost of a task of `ts` can be multiplied
+/// without exceeding its deadline, which no sensible test accepts.
+fn cost_limit(ts: &[Task]) -> RBig {
+    ts.iter()
+      .filter(|t| t.cost > 0)
+      .map(|t| RBig::from(t.deadline) / RBig::from(t.cost))
+      .min()
+      .unwrap_or(RBig::ONE)
+}
+
+/// Returns task-set `ts` with the cost of each task scaled by `factor`.
+pub fn scale_costs(ts: &[Task], factor: &RBig) -> Box<[Task]> {
+    ts.iter()
+      .map(|t| Task { cost: scale(t.cost, factor), ..*t })
+      .collect()
+}
+
+/// Returns the largest factor by which the costs of the tasks of `ts` can be scaled
+/// while `test` accepts it, up to `precision`; for an implicit task-set, this times
+/// its utilization is its _breakdown utilization_.
+///
+/// Returns `None` if `test` does not accept `ts` even with zero costs.
+///
+/// # Panics
+///
+/// Panics if `precision` is not positive.
+pub fn breakdown(ts: &[Task], precision: &RBig, test: impl Fn(&[Task]) -> bool) -> Option<RBig> {
+    largest(cost_limit(ts), precision, |f| test(&scale_costs(ts, f)))
+}
+
+/// Returns the largest factor by which the costs of the tasks of system `sys` can be
+/// scaled while `test` accepts it, up to `precision`.
+///
+/// Requests are left unchanged; the scaled cost of each task is never less than the
+/// [time it spends in critical sections](`System::request_time`).
+///
+/// Returns `None` if `test` does not accept `sys` with the costs of its tasks reduced to
+/// their time in critical sections.
+///
+/// # Panics
+///
+/// Panics if `precision` is not positive.
+pub fn cost_factor<K>(
+    sys: &System<'_, K>,
+    precision: &RBig,
+    test: impl Fn(&System<'_, K>) -> bool
+) -> Option<RBig>
+where K: RequestKind, K::Base<Request>: Clone {
+    let ts = sys.tasks();
+
+    largest(cost_limit(ts), precision, |f| {
+        let scaled = ts.iter().enumerate().map(|(i, t)| {
+            Task { cost: scale(t.cost, f).max(sys.request_time(i)), ..*t }
+        }).collect::<Box<_>>();
+
+        test(&sys.with_tasks(&scaled))
+    })
+}
+
+/// Returns the largest factor by which the lengths of the requests of system `sys` can
+/// be scaled while `test` accepts it, up to `precision`.
+///
+/// Costs are left unchanged, and hence bound the factor: no task can spend more time
+/// in critical sections than its cost.
+///
+/// Returns `None` if `sys` has no requests, or if `test` does not accept `sys` even
+/// with zero-length requests.
+///
+/// # Panics
+///
+/// Panics if `precision` is not positive.
+pub fn section_factor<K>(
+    sys: &System<'_, K>,
+    precision: &RBig,
+    test: impl Fn(&System<'_, K>) -> bool
+) -> Option<RBig>
+where K: RequestKind {
+    let hi = (0 .. sys.num_tasks()).filter(|i| sys.request_time(*i) > 0).map(|i| {
+        RBig::from(sys.task(i).cost) / RBig::from(sys.request_time(i))
+    }).min()?;
+
+    largest(hi, precision, |f| {
+        test(&sys.map_requests(|_, req| Request { length: scale(req.length, f), ..req }))
+    })
+}
+
+/// Returns the least speedup of the CPUs of system `sys` that makes `test` accept it,
+/// up to `precision`, i.e. the inverse of the largest factor by which both costs and
+/// request lengths can be scaled. The result is less than `1` if `sys` is accepted and
+/// could run on slower CPUs.
+///
+/// Returns `None` if `test` does not accept `sys` at any speed.
+///
+/// # Panics
+///
+/// Panics if `precision` is not positive.
+pub fn speedup<K>(
+    sys: &System<'_, K>,
+    precision: &RBig,
+    test: impl Fn(&System<'_, K>) -> bool
+) -> Option<RBig>
+where K: RequestKind, K::Base<Request>: Clone {
+    let ts = sys.tasks();
+
+    let factor = largest(cost_limit(ts), precision, |f| {
+        let scaled = scale_costs(ts, f);
+        let sys = sys.with_tasks(&scaled);
+
+        test(&sys.map_requests(|_, req| Request { length: scale(req.length, f), ..req }))
+    })?;
+
+    (factor > RBig::ZERO).then(|| RBig::ONE / factor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{rsrc::Mutex, bound::uni};
+
+    fn precision() -> RBig {
+        RBig::ONE / RBig::from(1000)
+    }
+
+    /// Tests whether `x` is at most `y` and within the precision of it.
+    fn close(x: RBig, y: RBig) -> bool {
+        x <= y && y - x <= precision()
+    }
+
+    /// Two tasks, each making one request whose length is half its cost, and a test
+    /// that charges each task with the critical sections of the other.
+    fn system(ts: &[Task]) -> System<'_, Mutex> {
+        let mut sys = System::new(ts);
+        let rsrc = sys.add_rsrc();
+
+        sys.add_req(0, rsrc, Request { num: 1, length: 1 });
+        sys.add_req(1, rsrc, Request { num: 1, length: 2 });
+        sys
+    }
+
+    fn test(sys: &System<'_, Mutex>) -> bool {
+        (0 .. sys.num_tasks()).all(|k| {
+            let others = (0 .. sys.num_tasks()).filter(|i| *i != k)
+                                               .map(|i| sys.request_time(i))
+                                               .sum::<Time>();
+
+            sys.task(k).cost + others <= sys.task(k).deadline
+        })
+    }
+
+    #[test]
+    fn breakdown_edf() {
+        // EDF schedules any implicit task-set with utilization up to 1
+        let ts = [Task::new(1, 4), Task::new(2, 8)];
+        let f = breakdown(&ts, &precision(), |ts| uni::edf(ts, None)).unwrap();
+
+        assert!(close(f, RBig::from(2)));
+        assert_eq!(breakdown(&ts, &precision(), |_| false), None);
+        assert_eq!(breakdown(&ts, &precision(), |_| true), Some(RBig::from(4)));
+    }
+
+    #[test]
+    fn factors() {
+        let ts = [Task::new(2, 10), Task::new(4, 10)];
+        let sys = system(&ts);
+
+        // the second task fails once 4f + 1 > 10
+        let cost = cost_factor(&sys, &precision(), test).unwrap();
+        assert!(close(cost, RBig::from(9) / RBig::from(4)));
+
+        // requests can grow up to the cost of each task
+        assert_eq!(section_factor(&sys, &precision(), test), Some(RBig::from(2)));
+
+        // costs and requests of the second task can be doubled, but not more
+        let speed = speedup(&sys, &precision(), test).unwrap();
+        assert!(speed >= RBig::ONE / RBig::from(2) && speed < RBig::ONE);
+    }
+
+    #[test]
+    fn no_requests() {
+        let ts = [Task::new(2, 10)];
+        let sys = System::<Mutex>::new(&ts);
+
+        assert_eq!(section_factor(&sys, &precision(), test), None);
+        assert_eq!(speedup(&sys, &precision(), |_| false), None);
+    }
+}
@@ -1,7 +1,7 @@
 //! Structures and traits for resource-sharing protocols and analysis.
 
 use crate::{
-    task::{Task, Set, Time},
+    task::{Task, Set, AsTask, Time},
     rsrc::{System, RequestKind, Usage, TaskRequest}
 };
 
@@ -188,7 +188,10 @@ impl<'a, I> BoundBlocking for I where I: IntoIterator<Item = TaskRequest<'a>> {
 /// This struct implements `From<(&Task, &ObliviousData)>` which is the only
 /// intended method for its construction and describes its internal structure.
 /// The purpose of this structure is to allow calls on an iterator or container
-/// of (both or either) [`Task`]s and [`ObliviousData`] to act as a [`Set`].
+/// of (both or either) [`Task`]s and [`ObliviousData`] to act as a [`Set`], or
+/// as [`AsTask`] items, e.g. to be placed by a
+/// [`Partitioner`](`crate::partition::Partitioner`).
+#[derive(Clone, Copy)]
 pub struct ObliviousTask<'a, 'b> {
     task: &'a Task,
     data: &'b ObliviousData
@@ -212,11 +215,31 @@ impl Set for ObliviousTask<'_, '_> {
         )
     }
 
+    fn density(self) -> Relaxed {
+        Relaxed::from_parts_const(
+            Sign::Positive,
+            (self.task.cost + self.data.total.length).into(),
+            self.task.deadline.min(self.task.period).into()
+        )
+    }
+
     fn implicit(self) -> bool {
         self.task.period == self.task.deadline
     }
 
+    fn constrained(self) -> bool {
+        self.task.deadline <= self.task.period
+    }
+
     fn feasible(self) -> bool {
         self.task.cost + self.data.total.length <= self.task.period
     }
 }
+
+/// An `ObliviousTask` is analyzed as its [`Task`] with the same inflated cost
+/// used by its [`Set`] implementation.
+impl AsTask for ObliviousTask<'_, '_> {
+    fn as_task(&self) -> Task {
+        Task { cost: self.task.cost + self.data.total.length, ..*self.task }
+    }
+}
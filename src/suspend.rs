@@ -0,0 +1,297 @@
+//! The self-suspending task model.
+//!
+//! Jobs of a self-suspending task alternate computation and suspension segments,
+//! e.g. waiting for I/O or an accelerator; unlike suspensions due to locks, these
+//! are part of the task itself. Suspensions can be analyzed either _obliviously_,
+//! by treating them as computation (see [`SuspendingTask::oblivious`]), or by the
+//! suspension-aware analyses in this module.
+//!
+//! The analyses in this module can be composed with the blocking bounds computed by
+//! [`System::run`](`crate::rsrc::System::run`): if given, the total blocking bound
+//! of each task is added to its cost, as for [`ObliviousTask`](`crate::sharing::ObliviousTask`).
+
+use crate::{
+    task::{Task, AsTask, Set, Time},
+    sharing::ObliviousData,
+    bound::{gedf, gfp, uni}
+};
+
+use dashu::rational::Relaxed;
+
+/// A segment of a job of a self-suspending task.
+#[derive(Clone, Copy)]
+pub enum Segment {
+    /// Computation for the given time.
+    Exec(Time),
+    /// Suspension for (at most) the given time.
+    Suspend(Time)
+}
+
+/// A self-suspending task.
+#[derive(Clone)]
+pub struct SuspendingTask {
+    task: Task,
+    segments: Box<[Segment]>
+}
+
+impl SuspendingTask {
+    /// Constructs a new `SuspendingTask` with the parameters of `task`, except for its
+    /// cost, and the given sequence of segments.
+    ///
+    /// The cost of the task is the total length of its computation segments.
+    pub fn new(task: Task, segments: impl IntoIterator<Item = Segment>) -> Self {
+        let segments = segments.into_iter().collect::<Box<_>>();
+
+        let cost = segments.iter().map(|s| match s {
+            Segment::Exec(c) => *c,
+            Segment::Suspend(_) => 0
+        }).sum();
+
+        Self { task: Task { cost, ..task }, segments }
+    }
+
+    /// Returns the task without its suspensions, i.e. with the total length of its
+    /// computation segments as cost.
+    pub fn task(&self) -> &Task {
+        &self.task
+    }
+
+    /// Returns the segments of each job of the task.
+    pub fn segments(&self) -> &[Segment] {
+        &self.segments
+    }
+
+    /// Returns the total length of the suspension segments of the task.
+    pub fn suspension(&self) -> Time {
+        self.segments.iter().map(|s| match s {
+            Segment::Exec(_) => 0,
+            Segment::Suspend(s) => *s
+        }).sum()
+    }
+
+    /// Returns the suspension-oblivious conversion of the task, i.e. the task with its
+    /// suspensions treated as computation.
+    pub fn oblivious(&self) -> Task {
+        Task { cost: self.task.cost + self.suspension(), ..self.task }
+    }
+}
+
+/// A `SuspendingTask` is analyzed by tests for non-suspending tasks through its
+/// suspension-oblivious conversion.
+impl AsTask for SuspendingTask {
+    fn as_task(&self) -> Task {
+        self.oblivious()
+    }
+}
+
+/// A `SuspendingTask` acts like its [`task`](`SuspendingTask::task`): suspensions do
+/// not contribute to its utilization or density.
+impl Set for &'_ SuspendingTask {
+    fn utilization(self) -> Relaxed {
+        self.task.utilization()
+    }
+
+    fn density(self) -> Relaxed {
+        self.task.density()
+    }
+
+    fn implicit(self) -> bool {
+        self.task.implicit()
+    }
+
+    fn constrained(self) -> bool {
+        self.task.constrained()
+    }
+
+    fn feasible(self) -> bool {
+        self.task.feasible()
+    }
+}
+
+/// Returns the tasks of `ts` with their costs inflated by `blocking`, if given, along
+/// with the suspension of each task.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+fn inflate(ts: &[SuspendingTask], blocking: Option<&[ObliviousData]>) -> (Box<[Task]>, Box<[Time]>) {
+    if let Some(b) = blocking {
+        assert_eq!(b.len(), ts.len());
+    }
+
+    let tasks = ts.iter().enumerate().map(|(i, t)| {
+        let extra = blocking.map_or(0, |b| b[i].total.length);
+        Task { cost: t.task.cost + extra, ..t.task }
+    }).collect();
+
+    (tasks, ts.iter().map(SuspendingTask::suspension).collect())
+}
+
+/// Suspension-aware response-time analysis for uniprocessor fixed-priority scheduling.
+///
+/// The task under analysis is treated obliviously, while the suspensions of
+/// higher-priority tasks are accounted for by bounding their workload within the
+/// response time of each of their jobs, which is equivalent to modeling them as
+/// release jitter as described in Chen et al.'s "Many suspensions, many problems: a
+/// review of self-suspending tasks in real-time systems" (Real-Time Systems, 2019).
+/// Priorities are given by [`Task::priority`] as for [`gfp`]. Each bound is also capped
+/// by that of the suspension-oblivious analysis of all tasks using [`uni::fp`].
+///
+/// Returns `None` if `ts` is not constrained, and otherwise a bound on the response
+/// time of each task, or `None` for tasks that cannot be shown to meet their deadline.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn fp(ts: &[SuspendingTask], blocking: Option<&[ObliviousData]>) -> Option<Box<[Option<Time>]>> {
+    if !ts.iter().constrained() {
+        return None;
+    }
+
+    let (tasks, susp) = inflate(ts, blocking);
+
+    let oblivious = uni::fp(tasks.iter().zip(&susp).map(|(t, s)| {
+        Task { cost: t.cost + s, ..*t }
+    }), None);
+
+    Some(gfp::analyze(&tasks, |k, hp, resp| {
+        let tk = Task { cost: tasks[k].cost + susp[k], ..tasks[k] };
+
+        let aware = gfp::fixed_point(&tk, |r| {
+            tk.cost + hp.iter()
+                        .map(|i| gedf::carry_in(&tasks[*i], resp[*i], r))
+                        .sum::<Time>()
+        });
+
+        match (aware, oblivious[k]) {
+            (Some(a), Some(o)) => Some(a.min(o)),
+            (a, o) => a.or(o)
+        }
+    }))
+}
+
+/// Suspension-aware response-time analysis for uniprocessor EDF.
+///
+/// As in [`fp`], the task under analysis is treated obliviously and the workload of
+/// every other task is bounded using its response time; it is further limited to jobs
+/// with deadlines no later than that of the task under analysis, as in the response-time
+/// analysis for global EDF in [`gedf::rta`]. Response-time bounds start at each task's
+/// deadline and are iteratively refined until no bound changes. Since this analysis does
+/// not account for the demand of other tasks over a busy period, the task-set is also
+/// accepted if it passes the suspension-oblivious test [`uni::edf`].
+///
+/// Returns the result of the test if `ts` is constrained, otherwise `None`.
+///
+/// # Panics
+///
+/// Panics if `blocking` is given and its length differs from that of `ts`.
+pub fn edf(ts: &[SuspendingTask], blocking: Option<&[ObliviousData]>) -> Option<bool> {
+    if !ts.iter().constrained() {
+        return None;
+    }
+
+    let (tasks, susp) = inflate(ts, blocking);
+
+    if uni::edf(tasks.iter().zip(&susp).map(|(t, s)| Task { cost: t.cost + s, ..*t }), None) {
+        return Some(true);
+    }
+
+    let mut resp = tasks.iter().map(|t| t.deadline).collect::<Box<_>>();
+    let mut ok = vec![false; tasks.len()];
+
+    loop {
+        let mut changed = false;
+
+        for k in 0 .. tasks.len() {
+            let tk = Task { cost: tasks[k].cost + susp[k], ..tasks[k] };
+
+            let Some(len) = tk.deadline.checked_sub(tk.jitter) else {
+                continue;
+            };
+
+            let r = gfp::fixed_point(&tk, |r| {
+                tk.cost + tasks.iter()
+                               .zip(&resp)
+                               .enumerate()
+                               .filter(|(i, _)| *i != k)
+                               .map(|(_, (ti, ri))| {
+                                   gedf::carry_in(ti, *ri, r)
+                                   .min(gedf::edf_workload(ti, len, ti.deadline - ri))
+                               })
+                               .sum::<Time>()
+            });
+
+            if let Some(r) = r {
+                ok[k] = true;
+
+                if r < resp[k] {
+                    resp[k] = r;
+                    changed = true;
+                }
+            }
+        }
+
+        if !changed {
+            break;
+        }
+    }
+
+    Some(ok.into_iter().all(|x| x))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::sharing::Bound;
+
+    use Segment::{Exec, Suspend};
+
+    /// A task that suspends for `susp` time units between two unit computations,
+    /// with higher priority than a non-suspending task of cost `cost`.
+    fn pair(susp: Time, cost: Time) -> [SuspendingTask; 2] {
+        [SuspendingTask::new(Task::new(0, 10), [Exec(1), Suspend(susp), Exec(1)]),
+         SuspendingTask::new(Task { priority: 1, ..Task::new(0, 12) }, [Exec(cost)])]
+    }
+
+    #[test]
+    fn segments() {
+        let [t, _] = pair(3, 4);
+
+        assert_eq!((t.task().cost, t.suspension(), t.oblivious().cost), (2, 3, 5));
+        assert_eq!(t.as_task().cost, 5);
+        assert_eq!(t.segments().len(), 3);
+    }
+
+    #[test]
+    fn fp_aware() {
+        let ts = pair(6, 6);
+
+        // the suspending task interferes at most twice for one unit each
+        assert_eq!(fp(&ts, None).as_deref(), Some([Some(8), Some(10)].as_slice()));
+        assert_eq!(uni::fp(ts.iter(), None).as_ref(), [Some(8), None]);
+    }
+
+    #[test]
+    fn fp_blocking() {
+        let ts = pair(3, 4);
+        let block = [ObliviousData::default(), Bound::new(2).into()];
+
+        assert_eq!(fp(&ts, None).as_deref(), Some([Some(5), Some(6)].as_slice()));
+        assert_eq!(fp(&ts, Some(&block)).as_deref(), Some([Some(5), Some(10)].as_slice()));
+    }
+
+    #[test]
+    fn edf_overload() {
+        assert_eq!(edf(&pair(3, 4), None), Some(true));
+        assert_eq!(edf(&pair(3, 10), None), Some(false));
+    }
+
+    #[test]
+    fn arbitrary_deadlines() {
+        let ts = [SuspendingTask::new(Task::new(0, 10).with_deadline(20), [Exec(1)])];
+
+        assert!(fp(&ts, None).is_none());
+        assert!(edf(&ts, None).is_none());
+    }
+}
@@ -13,6 +13,25 @@ use dashu::{
 /// number of milliseconds or microseconds.
 pub type Time = u64;
 
+/// Returns the hyperperiod of `periods`, i.e. their least common multiple, or `None` if
+/// it does not fit in a [`Time`]; the hyperperiod of no periods is `1`.
+///
+/// # Panics
+///
+/// Panics if a period is zero.
+pub fn hyperperiod(periods: impl IntoIterator<Item = Time>) -> Option<Time> {
+    periods.into_iter().try_fold(1, |acc: Time, p| {
+        assert!(p > 0);
+        let (mut a, mut b) = (acc, p);
+
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+
+        (acc / a).checked_mul(p)
+    })
+}
+
 /// A single task.
 #[derive(Clone, Copy)]
 pub struct Task {
@@ -20,21 +39,60 @@ pub struct Task {
     pub period: Time,
     /// The task's cost, also known as WCET (worst-case execution time).
     pub cost: Time,
-    /// The task's absolute deadline.
+    /// The task's relative deadline, measured from the arrival of each job.
     pub deadline: Time,
-    /// The task's priority, if defined.
-    pub priority: u64
+    /// The task's fixed priority, if defined; lower values mean higher priority.
+    ///
+    /// This is only meaningful under [`Policy::Fixed`].
+    pub priority: u64,
+    /// The task's preemption threshold, i.e. the priority that each of its jobs runs at
+    /// once started; only jobs of tasks with priority higher than or equal to the
+    /// [effective threshold](`Task::effective_threshold`) can preempt it.
+    ///
+    /// This is only meaningful under [`Policy::Fixed`]; [`u64::MAX`] means that the
+    /// task is fully preemptive. Thresholds are fully analyzed only by
+    /// [`bound::pt`](`crate::bound::pt`); [`bound::uni::fp`](`crate::bound::uni::fp`) and
+    /// the tests based on it only account for the blocking they induce, and the global
+    /// tests in [`bound::gfp`](`crate::bound::gfp`) reject them. Any other test ignores
+    /// thresholds, and should not be run on tasks with one.
+    pub threshold: u64,
+    /// The task's release jitter, i.e. the longest delay between the arrival of a job
+    /// and its release; periods and deadlines are measured from arrivals.
+    pub jitter: Time,
+    /// The task's minimum distance between consecutive releases, if positive.
+    ///
+    /// Together with the period and the jitter, this describes the releases of the task
+    /// with the _periodic with jitter and minimum distance_ (PJD) model; a jitter larger
+    /// than the period allows bursts of releases, which the minimum distance limits.
+    pub distance: Time,
+    /// The task's release offset, i.e. the arrival time of its first job.
+    ///
+    /// Offsets are only used by analyses of periodic task-sets that simulate or build an
+    /// actual schedule, i.e. [`exact::periodic`](`crate::bound::exact::periodic`) and
+    /// [`tt`](`crate::tt`). Every other schedulability test and blocking analysis treats
+    /// tasks as sporadic and assumes the worst-case phasing of their arrivals, such as
+    /// synchronous arrivals for uniprocessor fixed-priority scheduling, and hence safely
+    /// ignores offsets, although it may be pessimistic for periodic tasks with offsets.
+    pub offset: Time,
+    /// The length of the task's longest non-preemptive region, if any.
+    pub npr: Time
 }
 
 impl Task {
     /// Constructs a new `Task` with the given `cost` and `period`, implicit deadline
-    /// (equal to `period`) and maximum priority.
+    /// (equal to `period`), maximum priority, no jitter, minimum distance or offset and
+    /// full preemptivity, including no preemption threshold.
     pub fn new(cost: Time, period: Time) -> Self {
         Self {
             period,
             cost,
             deadline: period,
-            priority: 0
+            priority: 0,
+            threshold: u64::MAX,
+            jitter: 0,
+            distance: 0,
+            offset: 0,
+            npr: 0
         }
     }
 
@@ -43,16 +101,116 @@ impl Task {
         Self { deadline, ..self }
     }
 
-    /// Returns the task with EDF-like priority.
+    /// Returns the task with new preemption threshold `threshold`.
+    pub fn with_threshold(self, threshold: u64) -> Self {
+        Self { threshold, ..self }
+    }
+
+    /// Returns the effective preemption threshold of the task, i.e. the higher of its
+    /// priority and its threshold.
+    pub fn effective_threshold(&self) -> u64 {
+        self.priority.min(self.threshold)
+    }
+
+    /// Returns the task with new release jitter `jitter`.
+    pub fn with_jitter(self, jitter: Time) -> Self {
+        Self { jitter, ..self }
+    }
+
+    /// Returns the task with new minimum distance between releases `distance`.
+    pub fn with_distance(self, distance: Time) -> Self {
+        Self { distance, ..self }
+    }
+
+    /// Returns the task with new release offset `offset`.
+    pub fn with_offset(self, offset: Time) -> Self {
+        Self { offset, ..self }
+    }
+
+    /// Returns the task with a longest non-preemptive region of length `npr`.
+    pub fn with_npr(self, npr: Time) -> Self {
+        Self { npr, ..self }
+    }
+
+    /// Returns the largest number of jobs of the task that can be released within
+    /// an interval of length `len`, accounting for release jitter and minimum distance,
+    /// i.e. the arrival curve `η⁺(len)` of the PJD model.
+    pub fn max_jobs(&self, len: Time) -> Time {
+        let jobs = (len + self.jitter).div_ceil(self.period);
+
+        if self.distance > 0 {
+            jobs.min(len.div_ceil(self.distance))
+        } else {
+            jobs
+        }
+    }
+
+    /// Returns the task with deadline-monotonic priority, i.e. with a fixed priority
+    /// equal to its relative deadline. See also [`prio::dm`](`crate::prio::dm`).
     ///
-    /// This priority is not correct for simulation purposes; it is equal to the
-    /// task's _absolute_ deadline (i.e. [`deadline`](`Task::deadline`)) and not
-    /// its _relative_ deadline as used by EDF schedulers. This priority measure
-    /// is nevertheless used to compute arrival blocking for certain classes of
-    /// protocols when run under EDF, hence its name.
-    pub fn edf(self) -> Self {
+    /// EDF does not prioritize jobs by task; analyses that need to compare tasks under
+    /// EDF should use [`Policy::Edf`] instead, which compares relative deadlines directly.
+    pub fn dm(self) -> Self {
         Self { priority: self.deadline, ..self }
     }
+
+    /// Returns the task with deadline-monotonic priority.
+    #[deprecated(note = "this assigns a fixed priority, not an EDF one; use `Task::dm` instead")]
+    pub fn edf(self) -> Self {
+        self.dm()
+    }
+}
+
+/// A scheduling policy, i.e. the criterion used to prioritize pending jobs.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Policy {
+    /// Fixed-priority scheduling, using the [`priority`](`Task::priority`) of each task.
+    #[default]
+    Fixed,
+    /// Earliest-deadline-first scheduling, prioritizing each job by its absolute deadline,
+    /// i.e. its arrival time plus the [`deadline`](`Task::deadline`) of its task.
+    Edf,
+    /// First-in-first-out scheduling, prioritizing each job by its arrival time.
+    Fifo
+}
+
+impl Policy {
+    /// Tests whether a job of task `a` may have priority higher than or equal to that of
+    /// a pending job of task `b` that arrived no later than it, i.e. whether `a` has a
+    /// preemption level no lower than that of `b`.
+    ///
+    /// Under [`Fixed`](`Policy::Fixed`) this compares priorities, and under
+    /// [`Edf`](`Policy::Edf`) it compares relative deadlines. Under [`Fifo`](`Policy::Fifo`)
+    /// it always holds, as the two jobs may arrive at the same time.
+    pub fn dominates(self, a: &Task, b: &Task) -> bool {
+        match self {
+            Policy::Fixed => a.priority <= b.priority,
+            Policy::Edf   => a.deadline <= b.deadline,
+            Policy::Fifo  => true
+        }
+    }
+}
+
+/// Trait for objects that can be analyzed as a single [`Task`].
+///
+/// Schedulability tests that need the parameters of each task, rather than
+/// aggregate values such as those given by [`Set`], accept any iterator
+/// whose items implement this trait.
+pub trait AsTask {
+    /// Returns the `Task` that this object is analyzed as.
+    fn as_task(&self) -> Task;
+}
+
+impl AsTask for Task {
+    fn as_task(&self) -> Task {
+        *self
+    }
+}
+
+impl<T: AsTask + ?Sized> AsTask for &T {
+    fn as_task(&self) -> Task {
+        (**self).as_task()
+    }
 }
 
 /// Trait for tasks and collections of tasks (task-sets).
@@ -66,10 +224,21 @@ pub trait Set {
     /// to it if needed.
     fn utilization(self) -> Relaxed;
 
+    /// Returns the exact value of the total density of the task-set, i.e. the sum of
+    /// the cost of each task over the minimum of its deadline and period.
+    ///
+    /// Density equals utilization for implicit task-sets and exceeds it otherwise; as
+    /// for [`utilization`](`Set::utilization`), the result is not canonicalized.
+    fn density(self) -> Relaxed;
+
     /// Tests if the task-set has all implicit tasks, i.e. if all their deadlines
     /// are equal to their periods.
     fn implicit(self) -> bool;
 
+    /// Tests if the task-set has all constrained tasks, i.e. if all their deadlines
+    /// are no greater than their periods.
+    fn constrained(self) -> bool;
+
     /// Tests if the task-set is feasible, i.e. if its utilization is no greater than `1`.
     fn feasible(self) -> bool;
 }
@@ -84,10 +253,22 @@ impl Set for &'_ Task {
         )
     }
 
+    fn density(self) -> Relaxed {
+        Relaxed::from_parts_const(
+            Sign::Positive,
+            self.cost.into(),
+            self.deadline.min(self.period).into()
+        )
+    }
+
     fn implicit(self) -> bool {
         self.period == self.deadline
     }
 
+    fn constrained(self) -> bool {
+        self.deadline <= self.period
+    }
+
     fn feasible(self) -> bool {
         self.cost <= self.period
     }
@@ -96,9 +277,9 @@ impl Set for &'_ Task {
 /// Any collection of `Set`s (including [`Task`]) is a `Set`, and is treated as if each
 /// of its elements were a task.
 ///
-/// Note that, while `feasible` and `implicit` are correct for a `Set` of `Set`s if it
-/// represents a cluster, `utilization` will _sum_ the utilizations of each cluster.
-/// This may or may not be desirable; to prevent this, call `utilization` on each `Set`
+/// Note that, while `feasible`, `implicit` and `constrained` are correct for a `Set` of `Set`s if it
+/// represents a cluster, `utilization` and `density` will _sum_ the values of each cluster.
+/// This may or may not be desirable; to prevent this, call them on each `Set`
 /// individually and [collect](`Iterator::collect`) the result.
 impl<I, T: Set> Set for I where I: IntoIterator<Item = T> {
     fn utilization(self) -> Relaxed {
@@ -111,13 +292,85 @@ impl<I, T: Set> Set for I where I: IntoIterator<Item = T> {
         out
     }
 
+    fn density(self) -> Relaxed {
+        let mut out = Relaxed::default();
+
+        for x in self {
+            out += x.density();
+        }
+
+        out
+    }
+
     fn implicit(self) -> bool {
         self.into_iter()
             .all(T::implicit)
     }
 
+    fn constrained(self) -> bool {
+        self.into_iter()
+            .all(T::constrained)
+    }
+
     fn feasible(self) -> bool {
         self.into_iter()
             .all(T::feasible)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builders() {
+        let t = Task::new(2, 10).with_deadline(8).with_jitter(3).with_offset(4);
+
+        assert_eq!((t.period, t.cost, t.deadline, t.jitter, t.offset), (10, 2, 8, 3, 4));
+        assert_eq!((Task::new(2, 10).jitter, Task::new(2, 10).offset), (0, 0));
+    }
+
+    #[test]
+    fn max_jobs_jitter() {
+        let t = Task::new(1, 10);
+
+        assert_eq!((t.max_jobs(1), t.max_jobs(10), t.max_jobs(11)), (1, 1, 2));
+
+        // a job released late and the next one released early are closer than the period
+        let t = t.with_jitter(3);
+        assert_eq!((t.max_jobs(7), t.max_jobs(8), t.max_jobs(18)), (1, 2, 3));
+    }
+
+    #[test]
+    fn offsets_ignored() {
+        use crate::bound::uni;
+
+        let ts = [Task::new(2, 5), Task { priority: 1, ..Task::new(3, 10) }];
+        let shifted = [ts[0].with_offset(1), ts[1].with_offset(4)];
+
+        assert_eq!(uni::fp(ts, None), uni::fp(shifted, None));
+        assert_eq!(uni::edf(ts, None), uni::edf(shifted, None));
+    }
+
+    #[test]
+    fn policies() {
+        let urgent = Task { priority: 1, ..Task::new(1, 10).with_deadline(5) };
+        let lax = Task { priority: 0, ..Task::new(1, 10) };
+
+        assert!(Policy::Fixed.dominates(&lax, &urgent));
+        assert!(!Policy::Fixed.dominates(&urgent, &lax));
+        assert!(Policy::Edf.dominates(&urgent, &lax));
+        assert!(!Policy::Edf.dominates(&lax, &urgent));
+        assert!(Policy::Fifo.dominates(&urgent, &lax) && Policy::Fifo.dominates(&lax, &urgent));
+
+        // ties dominate both ways
+        assert!(Policy::Fixed.dominates(&lax, &lax) && Policy::Edf.dominates(&urgent, &urgent));
+    }
+
+    #[test]
+    fn hyperperiods() {
+        assert_eq!(hyperperiod([4, 6, 10]), Some(60));
+        assert_eq!(hyperperiod([]), Some(1));
+        assert_eq!(hyperperiod([Time::MAX, 2]), None);
+    }
+}
@@ -0,0 +1,438 @@
+//! Time-triggered scheduling.
+//!
+//! A time-triggered system executes jobs according to a static [`Table`] computed
+//! offline, which specifies when and on which CPU each job executes within a
+//! hyperperiod and is then repeated cyclically.
+//!
+//! Since requests only bound the number and length of critical sections, and not where
+//! they occur within a job, mutual exclusion is guaranteed by never interleaving the
+//! execution of jobs of tasks that share a resource: once such a job starts, no job that
+//! shares a resource with it executes until it completes.
+
+use crate::{
+    task::{self, Task, Time},
+    rsrc::{System, Mutex}
+};
+
+use std::fmt;
+
+/// A contiguous interval of execution of a job on a CPU.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Slot {
+    /// The index of the task of the job.
+    pub task: usize,
+    /// The index of the job among those of its task within the hyperperiod.
+    pub job: Time,
+    /// The CPU that the job executes on.
+    pub cpu: usize,
+    /// The start of the slot, from the start of the hyperperiod.
+    pub start: Time,
+    /// The end of the slot, from the start of the hyperperiod.
+    pub end: Time
+}
+
+/// A static schedule over a hyperperiod.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Table {
+    hyperperiod: Time,
+    num_cpus: usize,
+    slots: Box<[Slot]>
+}
+
+impl Table {
+    /// Constructs a new `Table` of length `hyperperiod` on `num_cpus` CPUs with slots
+    /// `slots`, which are not checked; use [`verify`] to check a table against a system.
+    pub fn new(hyperperiod: Time, num_cpus: usize, slots: impl IntoIterator<Item = Slot>) -> Self {
+        let mut slots = slots.into_iter().collect::<Box<_>>();
+        slots.sort_unstable_by_key(|s| (s.start, s.cpu));
+
+        Self { hyperperiod, num_cpus, slots }
+    }
+
+    /// Returns the length of the table.
+    pub fn hyperperiod(&self) -> Time {
+        self.hyperperiod
+    }
+
+    /// Returns the number of CPUs of the table.
+    pub fn num_cpus(&self) -> usize {
+        self.num_cpus
+    }
+
+    /// Returns the slots of the table, in order of start time.
+    pub fn slots(&self) -> &[Slot] {
+        &self.slots
+    }
+
+    /// Returns the slots of the table on CPU `cpu`, in order of start time.
+    pub fn slots_on(&self, cpu: usize) -> impl Iterator<Item = &Slot> + '_ {
+        self.slots.iter().filter(move |s| s.cpu == cpu)
+    }
+
+    /// Writes the table to `w` as tab-separated text, with a header line followed by one
+    /// line for each slot.
+    pub fn export(&self, w: &mut impl fmt::Write) -> fmt::Result {
+        writeln!(w, "# hyperperiod {} cpus {}", self.hyperperiod, self.num_cpus)?;
+        writeln!(w, "cpu\tstart\tend\ttask\tjob")?;
+
+        for s in &self.slots {
+            writeln!(w, "{}\t{}\t{}\t{}\t{}", s.cpu, s.start, s.end, s.task, s.job)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns, for each pair of tasks of `sys`, whether they access a common resource.
+fn conflicts(sys: &System<Mutex>) -> Box<[Box<[bool]>]> {
+    (0 .. sys.num_tasks()).map(|i| {
+        (0 .. sys.num_tasks()).map(|j| {
+            sys.reqs_by(i).iter().zip(sys.reqs_by(j)).any(|(a, b)| a.num > 0 && b.num > 0)
+        }).collect()
+    }).collect()
+}
+
+/// Returns the hyperperiod of the tasks of `ts`, if they can be scheduled by a table,
+/// i.e. if they have no release jitter and each job completes within the period it is
+/// released in.
+fn table_hyperperiod(ts: &[Task]) -> Option<Time> {
+    if ts.iter().any(|t| t.jitter > 0 || t.offset + t.deadline > t.period) {
+        return None;
+    }
+
+    task::hyperperiod(ts.iter().map(|t| t.period))
+}
+
+/// A job to be scheduled within a table.
+struct Job {
+    task: usize,
+    index: Time,
+    release: Time,
+    deadline: Time,
+    left: Time,
+    started: bool,
+    cpu: Option<usize>
+}
+
+/// Synthesizes a table for the tasks of system `sys` on `num_cpus` CPUs by list
+/// scheduling their jobs over the hyperperiod by EDF, with ties broken by task index.
+///
+/// Each job of a task is released at the task's offset plus a multiple of its period.
+/// A job is only scheduled if no started job that shares a resource with it is
+/// incomplete, and keeps its CPU when it executes again right after a slot.
+///
+/// Returns `None` if a task has release jitter or a deadline past the end of the
+/// period it is released in, if the hyperperiod does not fit in a [`Time`], or if some
+/// job misses its deadline.
+pub fn synthesize(sys: &System<Mutex>, num_cpus: usize) -> Option<Table> {
+    let ts = sys.tasks();
+    let hyper = table_hyperperiod(ts)?;
+    let conflicts = conflicts(sys);
+
+    let mut jobs = ts.iter().enumerate().flat_map(|(task, t)| {
+        (0 .. hyper / t.period).map(move |index| {
+            let release = t.offset + index * t.period;
+
+            Job {
+                task,
+                index,
+                release,
+                deadline: release + t.deadline,
+                left: t.cost,
+                started: false,
+                cpu: None
+            }
+        })
+    }).filter(|j| j.left > 0).collect::<Vec<_>>();
+
+    jobs.sort_unstable_by_key(|j| (j.deadline, j.task, j.index));
+
+    let mut slots = Vec::<Slot>::new();
+    let mut now = 0;
+
+    while jobs.iter().any(|j| j.left > 0) {
+        if jobs.iter().any(|j| j.left > 0 && j.deadline <= now) {
+            return None;
+        }
+
+        // pick the jobs to execute, in order of priority
+        let mut picked = Vec::new();
+
+        for k in 0 .. jobs.len() {
+            if picked.len() == num_cpus {
+                break;
+            }
+
+            let j = &jobs[k];
+
+            if j.left == 0 || j.release > now {
+                continue;
+            }
+
+            let blocked = jobs.iter().enumerate().any(|(o, other)| {
+                o != k && conflicts[j.task][other.task] && other.left > 0
+                       && (other.started || picked.contains(&o))
+            });
+
+            if !blocked {
+                picked.push(k);
+            }
+        }
+
+        // picked jobs keep their CPU if still free, and otherwise take the first free one
+        let mut free = vec![true; num_cpus];
+
+        for k in &picked {
+            if let Some(c) = jobs[*k].cpu.filter(|c| free[*c]) {
+                free[c] = false;
+            } else {
+                jobs[*k].cpu = None;
+            }
+        }
+
+        for k in &picked {
+            if jobs[*k].cpu.is_none() {
+                let c = free.iter().position(|f| *f).unwrap();
+                free[c] = false;
+                jobs[*k].cpu = Some(c);
+            }
+        }
+
+        // the selection holds until the next release, completion or deadline
+        let next = jobs.iter().flat_map(|j| {
+            [Some(j.release), (j.left > 0).then_some(j.deadline)]
+        }).flatten().chain(picked.iter().map(|k| now + jobs[*k].left)).filter(|t| *t > now).min()?;
+
+        for (k, job) in jobs.iter_mut().enumerate() {
+            if !picked.contains(&k) {
+                job.cpu = None;
+                continue;
+            }
+
+            let cpu = job.cpu.unwrap();
+
+            job.started = true;
+            job.left -= next - now;
+
+            match slots.iter_mut().rev().find(|s| s.cpu == cpu) {
+                Some(s) if s.end == now && s.task == job.task && s.job == job.index => s.end = next,
+                _ => slots.push(Slot { task: job.task, job: job.index, cpu, start: now, end: next })
+            }
+        }
+
+        now = next;
+    }
+
+    Some(Table::new(hyper, num_cpus, slots))
+}
+
+/// A violation of the constraints of a system by a table.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Violation {
+    /// The length of the table is not the hyperperiod of the tasks, or the tasks cannot
+    /// be scheduled by a table, as for [`synthesize`].
+    Hyperperiod,
+    /// A slot refers to a non-existent task, job or CPU, or is empty or outside the table.
+    Malformed(Slot),
+    /// Two slots overlap on the same CPU.
+    Overlap(Slot, Slot),
+    /// A job executes on two CPUs at the same time.
+    Parallel(Slot, Slot),
+    /// A slot is outside the interval between the release and the deadline of its job.
+    Window(Slot),
+    /// A job executes for `executed` time units instead of the cost of its task.
+    Cost {
+        /// The index of the task of the job.
+        task: usize,
+        /// The index of the job among those of its task.
+        job: Time,
+        /// The total execution of the job in the table.
+        executed: Time
+    },
+    /// The executions of two jobs of tasks that share a resource are interleaved, so
+    /// that their critical sections may overlap.
+    Conflict(Slot, Slot)
+}
+
+/// Verifies table `table` against system `sys`, checking that each job executes for
+/// exactly the cost of its task, on at most one CPU at a time, and between its release
+/// and its deadline, and that jobs of tasks that share a resource are not interleaved.
+///
+/// Returns all violations found, or an empty list if the table is valid.
+pub fn verify(table: &Table, sys: &System<Mutex>) -> Box<[Violation]> {
+    let ts = sys.tasks();
+
+    let Some(hyper) = table_hyperperiod(ts).filter(|h| *h == table.hyperperiod) else {
+        return Box::new([Violation::Hyperperiod]);
+    };
+
+    let mut out = Vec::new();
+
+    let (valid, malformed) = table.slots.iter().copied().partition::<Vec<_>, _>(|s| {
+        s.task < ts.len() && s.job < hyper / ts[s.task].period
+                          && s.cpu < table.num_cpus
+                          && s.start < s.end
+                          && s.end <= hyper
+    });
+
+    out.extend(malformed.into_iter().map(Violation::Malformed));
+
+    let overlap = |a: &Slot, b: &Slot| a.start < b.end && b.start < a.end;
+
+    for (i, a) in valid.iter().enumerate() {
+        for b in &valid[i + 1 ..] {
+            if !overlap(a, b) {
+                continue;
+            } else if a.cpu == b.cpu {
+                out.push(Violation::Overlap(*a, *b));
+            } else if (a.task, a.job) == (b.task, b.job) {
+                out.push(Violation::Parallel(*a, *b));
+            }
+        }
+    }
+
+    for s in &valid {
+        let t = &ts[s.task];
+        let release = t.offset + s.job * t.period;
+
+        if s.start < release || s.end > release + t.deadline {
+            out.push(Violation::Window(*s));
+        }
+    }
+
+    // the execution of each job, from its first to its last slot
+    let mut spans = Vec::<(Slot, Time)>::new();
+
+    for (task, t) in ts.iter().enumerate() {
+        for job in 0 .. hyper / t.period {
+            let mine = valid.iter().filter(|s| (s.task, s.job) == (task, job));
+            let executed = mine.clone().map(|s| s.end - s.start).sum();
+
+            if executed != t.cost {
+                out.push(Violation::Cost { task, job, executed });
+            }
+
+            let first = mine.clone().min_by_key(|s| s.start);
+            let end = mine.map(|s| s.end).max();
+
+            if let (Some(first), Some(end)) = (first, end) {
+                spans.push((*first, end));
+            }
+        }
+    }
+
+    let conflicts = conflicts(sys);
+
+    for (i, (a, a_end)) in spans.iter().enumerate() {
+        for (b, b_end) in &spans[i + 1 ..] {
+            if conflicts[a.task][b.task] && a.start < *b_end && b.start < *a_end {
+                out.push(Violation::Conflict(*a, *b));
+            }
+        }
+    }
+
+    out.into_boxed_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::rsrc::Request;
+
+    fn slot(task: usize, job: Time, cpu: usize, start: Time, end: Time) -> Slot {
+        Slot { task, job, cpu, start, end }
+    }
+
+    /// Two tasks on two CPUs that share a resource if `shared`.
+    fn pair(ts: &[Task], shared: bool) -> System<'_, Mutex> {
+        let mut sys = System::new(ts);
+
+        if shared {
+            let rsrc = sys.add_rsrc();
+            sys.add_req(0, rsrc, Request { num: 1, length: 1 });
+            sys.add_req(1, rsrc, Request { num: 1, length: 1 });
+        }
+
+        sys
+    }
+
+    #[test]
+    fn uniprocessor() {
+        let ts = [Task::new(1, 4), Task::new(2, 6), Task::new(3, 12)];
+        let sys = System::new(&ts);
+        let table = synthesize(&sys, 1).unwrap();
+
+        // the last task is preempted twice by earlier deadlines
+        assert_eq!(table.hyperperiod(), 12);
+        assert_eq!(table.slots_on(0).filter(|s| s.task == 2).count(), 3);
+        assert_eq!(table.slots().len(), 8);
+        assert!(verify(&table, &sys).is_empty());
+
+        let mut out = String::new();
+        table.export(&mut out).unwrap();
+        assert!(out.starts_with("# hyperperiod 12 cpus 1\ncpu\tstart\tend\ttask\tjob\n0\t0\t1\t0\t0\n"));
+    }
+
+    #[test]
+    fn sharing() {
+        let ts = [Task::new(2, 4), Task::new(2, 4)];
+
+        let table = synthesize(&pair(&ts, false), 2).unwrap();
+        assert_eq!(table.slots(), [slot(0, 0, 0, 0, 2), slot(1, 0, 1, 0, 2)]);
+
+        // jobs that share a resource run one after the other, even with a free CPU
+        let table = synthesize(&pair(&ts, true), 2).unwrap();
+        assert_eq!(table.slots(), [slot(0, 0, 0, 0, 2), slot(1, 0, 0, 2, 4)]);
+        assert!(verify(&table, &pair(&ts, true)).is_empty());
+
+        let ts = [Task::new(3, 4), Task::new(3, 4)];
+        assert!(synthesize(&pair(&ts, false), 2).is_some());
+        assert_eq!(synthesize(&pair(&ts, true), 2), None);
+    }
+
+    #[test]
+    fn offsets() {
+        let ts = [Task::new(2, 4).with_deadline(2), Task::new(2, 4).with_deadline(2).with_offset(2)];
+        let table = synthesize(&pair(&ts, false), 1).unwrap();
+
+        assert_eq!(table.slots(), [slot(0, 0, 0, 0, 2), slot(1, 0, 0, 2, 4)]);
+        assert_eq!(synthesize(&pair(&[ts[0], ts[0]], false), 1), None);
+        assert_eq!(synthesize(&pair(&[ts[0], ts[1].with_offset(3)], false), 1), None);
+
+        let early = slot(1, 0, 1, 1, 3);
+        let table = Table::new(4, 2, [slot(0, 0, 0, 0, 2), early]);
+        assert_eq!(verify(&table, &pair(&ts, false)).as_ref(), [Violation::Window(early)]);
+    }
+
+    #[test]
+    fn violations() {
+        let ts = [Task::new(2, 4), Task::new(2, 4)];
+        let sys = pair(&ts, true);
+        let check = |slots: &[Slot]| verify(&Table::new(4, 2, slots.iter().copied()), &sys);
+
+        assert_eq!(verify(&Table::new(8, 2, []), &sys).as_ref(), [Violation::Hyperperiod]);
+        assert_eq!(check(&[slot(0, 0, 0, 0, 2), slot(1, 0, 0, 2, 4)]).as_ref(), []);
+
+        let bad = slot(0, 0, 2, 0, 2);
+        assert_eq!(check(&[bad, slot(1, 0, 0, 2, 4)]).as_ref(), [
+            Violation::Malformed(bad),
+            Violation::Cost { task: 0, job: 0, executed: 0 }
+        ]);
+
+        let (a, b) = (slot(0, 0, 0, 0, 2), slot(1, 0, 1, 1, 3));
+        assert_eq!(check(&[a, b]).as_ref(), [Violation::Conflict(a, b)]);
+
+        let (a, b) = (slot(0, 0, 0, 0, 2), slot(1, 0, 0, 1, 3));
+        assert_eq!(check(&[a, b]).as_ref(), [Violation::Overlap(a, b), Violation::Conflict(a, b)]);
+
+        let (a, b) = (slot(0, 0, 0, 0, 1), slot(0, 0, 1, 0, 1));
+        assert_eq!(check(&[a, b, slot(1, 0, 0, 2, 4)]).as_ref(), [Violation::Parallel(a, b)]);
+
+        let late = slot(1, 0, 0, 3, 5);
+        assert_eq!(check(&[slot(0, 0, 0, 0, 2), late]).as_ref(), [
+            Violation::Malformed(late),
+            Violation::Cost { task: 1, job: 0, executed: 0 }
+        ]);
+    }
+}